@@ -0,0 +1,54 @@
+//! Throughput of the wire <-> float sample conversions
+//!
+//! Run with `cargo bench --bench convert`.
+
+use bladerf::{brf_cf32_slice_to_ci16, brf_ci16_slice_to_cf32, brf_ci16_to_cf32};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use num_complex::Complex;
+
+const SIZES: [usize; 2] = [8 * 1024, 32 * 1024];
+
+fn ci16_to_cf32(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ci16_to_cf32");
+    for size in SIZES {
+        let src: Vec<_> = (0..size)
+            .map(|i| Complex::new((i % 4096) as i16 - 2048, (i % 512) as i16))
+            .collect();
+        let mut dst = vec![Complex::default(); size];
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("slice", size), &src, |b, src| {
+            b.iter(|| brf_ci16_slice_to_cf32(src, &mut dst))
+        });
+        group.bench_with_input(BenchmarkId::new("scalar", size), &src, |b, src| {
+            b.iter(|| {
+                for (out, x) in dst.iter_mut().zip(src) {
+                    *out = brf_ci16_to_cf32(*x);
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn cf32_to_ci16(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cf32_to_ci16");
+    for size in SIZES {
+        let src: Vec<_> = (0..size)
+            .map(|i| {
+                let phase = i as f32 * 0.01;
+                Complex::new(phase.cos(), phase.sin())
+            })
+            .collect();
+        let mut dst = vec![Complex::default(); size];
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("slice", size), &src, |b, src| {
+            b.iter(|| brf_cf32_slice_to_ci16(src, &mut dst))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, ci16_to_cf32, cf32_to_ci16);
+criterion_main!(benches);
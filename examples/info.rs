@@ -36,9 +36,10 @@ pub fn main() -> anyhow::Result<()> {
             .map_err(|e| println!("{e:?}"));
         println!();
 
-        for ch in [Channel::Rx0, Channel::Rx1, Channel::Tx0, Channel::Tx1] {
+        let board = dev.board_kind().context("Failed to identify board")?;
+        for &ch in Channel::all(board) {
             let _ = print_channel_info(&dev, ch)
-                .context(format!("Failed to print channel information for {:?}", ch))
+                .context(format!("Failed to print channel information for {ch}"))
                 .map_err(|e| println!("{e:?}"));
         }
     }
@@ -47,36 +48,16 @@ pub fn main() -> anyhow::Result<()> {
 }
 
 fn print_device_info(dev: &BladeRF) -> anyhow::Result<()> {
-    let fw_version = dev
-        .firmware_version()
-        .context("Failed to retrieve firmware version")?;
-    println!("  Firmware Version: {fw_version}");
-
-    let fpga_version = dev
-        .fpga_version()
-        .context("Failed to retrieve FPGA version")?;
-    println!("  FPGA Version: {fpga_version}");
-
-    let fpga_size = dev
-        .get_fpga_size()
-        .context("Failed to retrieve FPGA size")?;
-    println!("  FPGA Size: {:?}", fpga_size);
-
-    let is_configured = dev
-        .is_fpga_configured()
-        .context("Failed to check if FPGA is configured")?;
-    println!("  FPGA Configured: {}", is_configured);
-
-    let serial = dev
-        .get_serial()
-        .context("Failed to retrieve serial number")?;
-    println!("  Serial Number: {}", serial);
+    let info = dev
+        .full_info()
+        .context("Failed to retrieve device information")?;
+    println!("{info}");
 
     Ok(())
 }
 
 fn print_channel_info(dev: &BladeRF, channel: Channel) -> anyhow::Result<()> {
-    println!("  Channel {channel:?}");
+    println!("  Channel {channel}");
 
     // frequency
     let freq = dev
@@ -176,10 +157,17 @@ fn print_loopback_info(dev: &BladeRF) -> anyhow::Result<()> {
 }
 
 fn print_sampling_info(dev: &BladeRF) -> anyhow::Result<()> {
-    // let sampling = dev
-    //     .get_sampling()
-    //     .context("Failed to retrieve sampling mode")?;
-    // println!("  Sampling Mode: {:?}", sampling);
+    if dev
+        .supports_sampling()
+        .context("Failed to identify board")?
+    {
+        let sampling = dev
+            .get_sampling()
+            .context("Failed to retrieve sampling mode")?;
+        println!("  Sampling Mode: {:?}", sampling);
+    } else {
+        println!("  Sampling Mode: not selectable on this board");
+    }
 
     let rx_mux = dev.get_rx_mux().context("Failed to retrieve RX Mux mode")?;
     println!("  RX Mux Mode: {:?}", rx_mux);
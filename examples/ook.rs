@@ -223,8 +223,6 @@ fn tx(
     let mut samples = vec![Complex::<i16>::ZERO; c.buffer_size as usize];
 
     let mut last_print = Instant::now();
-    let mut sample_count = 0;
-    let mut bytes = 0;
 
     while RUNNING.load(Ordering::Acquire) {
         // Fill the samples buffer according to the bitstream
@@ -249,14 +247,11 @@ fn tx(
             .sync_tx(&samples, meta.as_mut(), timeout)
             .context("Transmit samples")?;
 
-        sample_count += samples.len();
-        bytes += samples.len() * std::mem::size_of_val(&samples[0]);
-
         let now = Instant::now();
-        let elapsed = now.saturating_duration_since(last_print);
-        if elapsed.as_secs() >= 1 {
-            let mib = bytes as f64 / 1_000_000.0 / elapsed.as_secs_f64();
-            let tx_stats = format!("TX: {mib:.1}MiB / s, {sample_count:.1}M samples");
+        if now.saturating_duration_since(last_print).as_secs() >= 1 {
+            let stats = device.tx_stats();
+            let mib = stats.throughput() / 1_000_000.0;
+            let tx_stats = format!("TX: {mib:.1}MiB / s, {} samples", stats.total_samples());
 
             // Update UI state
             {
@@ -265,8 +260,6 @@ fn tx(
             }
 
             last_print = now;
-            bytes = 0;
-            sample_count = 0;
         }
     }
 
@@ -275,7 +268,7 @@ fn tx(
 
 impl Config {
     fn write(&self, device: &bladerf::BladeRF, channel: Channel) -> anyhow::Result<()> {
-        println!("Writing parameters for {channel:?}");
+        println!("Writing parameters for {channel}");
 
         device.set_sample_rate(channel, self.sample_rate_hz)?;
         device.set_frequency(channel, self.frequency_hz)?;
@@ -384,30 +377,11 @@ fn tui_app() -> anyhow::Result<()> {
     );
     let device = bladerf::BladeRF::open_first().context("Failed to list BladeRF devices")?;
     println!("Found: {:?}", device.info().map(|i| i.serial()));
-    let serial_number = device
-        .get_serial()
-        .context("Failed to get bladerf serial number")?;
 
     println!("Resetting device...");
-    let _ = device
-        .device_reset()
-        .map_err(|e| println!("Failed to reset device: {e:?}"));
-
-    let start = Instant::now();
-    let device = 'outer: loop {
-        for info in bladerf::get_device_list().unwrap_or_default() {
-            println!("Found: {:?}", info.serial());
-            if info.serial() == serial_number {
-                if let Ok(dev) = info.open() {
-                    break 'outer dev;
-                }
-            }
-        }
-        if start.elapsed().as_secs() > 2 {
-            anyhow::bail!("Failed to open device after two seconds");
-        }
-        thread::sleep(Duration::from_millis(50));
-    };
+    let device = device
+        .reopen_after_reset(Duration::from_secs(2))
+        .context("Failed to reopen device after reset")?;
 
     println!("Opened device");
     let info = device.info().context("Failed to obtain device info")?;
@@ -1,12 +1,17 @@
 use crate::{error::*, sys::*, types::*};
 use enum_map::EnumMap;
 use ffi::{c_char, c_void, CStr, CString};
+use io::Write;
 use log::warn;
+use num_complex::Complex;
 use parking_lot::Mutex;
 use path::Path;
 use std::*;
-use sync::RwLock;
-use time::Duration;
+use sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    RwLock,
+};
+use time::{Duration, Instant};
 
 // Macro to simplify integer returns
 macro_rules! check_res {
@@ -20,10 +25,43 @@ macro_rules! check_res {
 pub const FPGA_BITSTREAM_VAR_NAME: &str = "BLADERF_RS_FPGA_BITSTREAM_PATH";
 
 /// BladeRF device object
+///
+/// The handle is `Send` and `Sync`, so share it between threads with an `Arc`. A second handle
+/// cannot be opened to the same device while this one is alive: libbladerf claims the USB
+/// interface exclusively, and the second open fails with [`Error::Nodev`] or [`Error::IO`].
+///
+/// # Threading
+/// libbladerf serializes control calls (tuning, gain, sample rate, ...) with a per-device lock,
+/// so they may be made from any thread. The synchronous RX and TX streams keep separate state,
+/// so full duplex operation with one thread calling [`BladeRF::sync_rx`] and another calling
+/// [`BladeRF::sync_tx`] is supported. Calling `sync_rx` (or `sync_tx`) from several threads at
+/// once is not: samples would be split between the callers in an unspecified order.
+///
+/// Both directions share the format recorded by the last [`BladeRF::sync_config`] call, so
+/// configure RX and TX with the same [`Format`] when streaming in both directions.
 pub struct BladeRF {
     device: *mut bladerf,
     enabled_modules: Mutex<EnumMap<Channel, bool>>,
     format_sync: RwLock<Option<Format>>,
+    /// Gain modes reported by libbladerf, populated on first query per channel
+    gain_modes: Mutex<EnumMap<Channel, Option<Vec<GainMode>>>>,
+    /// Gain range per channel, cleared when the channel is retuned
+    gain_ranges: Mutex<EnumMap<Channel, Option<Range>>>,
+    /// Set while a TX burst started with metadata has not been ended yet
+    tx_burst_open: AtomicBool,
+    /// Bias tees turned on through this handle, turned off again on Drop
+    bias_tees: Mutex<EnumMap<Channel, bool>>,
+    /// Wire format samples for [`BladeRF::sync_rx_cf32`], kept to avoid allocating per call
+    rx_scratch: Mutex<Vec<Complex<i16>>>,
+    /// RX overruns reported through metadata, see [`BladeRF::overruns`]
+    overruns: AtomicU64,
+    /// TX underruns reported through metadata, see [`BladeRF::underruns`]
+    underruns: AtomicU64,
+    rx_stats: StreamStats,
+    tx_stats: StreamStats,
+    /// Last successful [`BladeRF::sync_config_with`] per layout, for
+    /// [`BladeRF::sync_reconfigure_format`]
+    stream_configs: Mutex<EnumMap<ChannelLayout, Option<(Format, StreamConfig)>>>,
 }
 
 unsafe impl Send for BladeRF {}
@@ -40,24 +78,66 @@ impl Drop for BladeRF {
             }
         }
 
+        // Never leave DC on an antenna port after the handle goes away
+        let bias_tees = *self.bias_tees.get_mut();
+        for (channel, enabled) in bias_tees {
+            if enabled {
+                if let Err(e) = self.set_bias_tee(channel, false) {
+                    warn!("Failed to disable bias tee {channel} on Drop: {e:?}");
+                }
+            }
+        }
+
         unsafe { bladerf_close(self.device) }
     }
 }
 
 impl BladeRF {
+    fn from_device(device: *mut bladerf) -> Self {
+        Self {
+            device,
+            enabled_modules: Mutex::new(EnumMap::default()),
+            format_sync: RwLock::new(None),
+            gain_modes: Mutex::new(EnumMap::default()),
+            gain_ranges: Mutex::new(EnumMap::default()),
+            tx_burst_open: AtomicBool::new(false),
+            bias_tees: Mutex::new(EnumMap::default()),
+            rx_scratch: Mutex::new(Vec::new()),
+            overruns: AtomicU64::new(0),
+            underruns: AtomicU64::new(0),
+            rx_stats: StreamStats::new(),
+            tx_stats: StreamStats::new(),
+            stream_configs: Mutex::new(EnumMap::default()),
+        }
+    }
+
+    pub(crate) fn device_ptr(&self) -> *mut bladerf {
+        self.device
+    }
+
+    /// Open the first BladeRF device found
+    ///
+    /// # Errors
+    /// libbladerf reports open failures through its regular error codes, which map to:
+    /// - [`Error::Nodev`]: no matching device is attached. Some backends also report this when the
+    ///   device is already claimed by another process.
+    /// - [`Error::IO`]: the device was found but could not be claimed, which can also indicate that
+    ///   another process is holding it open.
+    /// - [`Error::Permission`]: the USB device node is not accessible to this user. On Linux this
+    ///   almost always means the bladeRF udev rules are not installed or the user is not in the
+    ///   `bladerf` group.
+    /// - [`Error::UpdateFw`]: the FX3 firmware is too old for this libbladerf.
     pub fn open_first() -> Result<Self> {
         log::info!("Opening first bladerf");
         let mut device = std::ptr::null_mut();
         let res = unsafe { bladerf_open(&mut device as *mut *mut _, ptr::null()) };
         check_res!(res);
-        Ok(Self {
-            device,
-            enabled_modules: Mutex::new(EnumMap::default()),
-            format_sync: RwLock::new(None),
-        })
+        Ok(Self::from_device(device))
     }
 
     /// Open a BladeRF device by identifier
+    ///
+    /// See [`BladeRF::open_first`] for the errors returned when opening fails.
     pub fn open_identifier(id: &str) -> Result<Self> {
         let mut device = std::ptr::null_mut();
         let c_string = ffi::CString::new(id)
@@ -65,14 +145,12 @@ impl BladeRF {
         let res = unsafe { bladerf_open(&mut device as *mut *mut _, c_string.as_ptr()) };
 
         check_res!(res);
-        Ok(Self {
-            device,
-            enabled_modules: Mutex::new(EnumMap::default()),
-            format_sync: RwLock::new(None),
-        })
+        Ok(Self::from_device(device))
     }
 
     /// Open a BladeRF device by devinfo object
+    ///
+    /// See [`BladeRF::open_first`] for the errors returned when opening fails.
     pub fn open_with_devinfo(devinfo: &DevInfo) -> Result<Self> {
         let mut devinfo_ptr = devinfo.0;
         let mut device = std::ptr::null_mut();
@@ -82,11 +160,19 @@ impl BladeRF {
         };
 
         check_res!(res);
-        Ok(Self {
-            device,
-            enabled_modules: Mutex::new(EnumMap::default()),
-            format_sync: RwLock::new(None),
-        })
+        Ok(Self::from_device(device))
+    }
+
+    /// Open the first BladeRF device, loading the FPGA bitstream from [`FPGA_BITSTREAM_VAR_NAME`]
+    /// only if the FPGA is not already configured.
+    pub fn open_first_with_fpga() -> Result<Self> {
+        let device = Self::open_first()?;
+        if device.is_fpga_configured()? {
+            log::info!("FPGA already configured, skipping bitstream load");
+        } else {
+            device.load_fpga_from_env()?;
+        }
+        Ok(device)
     }
 
     pub fn info(&self) -> Result<DevInfo> {
@@ -108,17 +194,22 @@ impl BladeRF {
     // http://www.nuand.com/libbladeRF-doc/v2.5.0/group___f_n___i_n_f_o.html
 
     pub fn get_serial(&self) -> Result<String> {
-        let mut serial_data = [0i8; BLADERF_SERIAL_LENGTH as usize];
-
-        let res = unsafe { bladerf_get_serial(self.device, serial_data.as_mut_ptr().cast()) };
+        let mut serial = bladerf_serial {
+            serial: [0; BLADERF_SERIAL_LENGTH as usize],
+        };
 
+        let res = unsafe { bladerf_get_serial_struct(self.device, &mut serial) };
         check_res!(res);
-        let serial_cstr = unsafe { CStr::from_ptr(serial_data.as_ptr().cast()) };
-        let serial_str = serial_cstr
-            .to_str()
-            .map_err(|e| Error::msg(format!("Serial number is not UTF-8: {e:?}")))?;
 
-        Ok(serial_str.to_string())
+        // Stop at the first NUL, or use the whole array if libbladerf filled it
+        let bytes: Vec<u8> = serial
+            .serial
+            .iter()
+            .map(|&c| c as u8)
+            .take_while(|&c| c != 0)
+            .collect();
+        String::from_utf8(bytes)
+            .map_err(|e| Error::msg(format!("Serial number is not UTF-8: {e:?}")))
     }
 
     pub fn get_fpga_size(&self) -> Result<bladerf_fpga_size> {
@@ -128,6 +219,39 @@ impl BladeRF {
         Ok(fpga_size)
     }
 
+    /// Like [`BladeRF::get_fpga_size`], as an [`FpgaSize`]
+    pub fn get_fpga_size_typed(&self) -> Result<FpgaSize> {
+        FpgaSize::try_from(self.get_fpga_size()?)
+    }
+
+    /// Expected size in bytes of an FPGA bitstream for this device
+    pub fn get_fpga_bytes(&self) -> Result<usize> {
+        let mut size: usize = 0;
+        let res = unsafe { bladerf_get_fpga_bytes(self.device, &mut size) };
+        check_res!(res);
+        Ok(size)
+    }
+
+    /// Where the currently loaded FPGA bitstream was loaded from
+    ///
+    /// [`FpgaSource::Flash`] means a bitstream is stored in flash and autoloaded at power on.
+    pub fn get_fpga_source(&self) -> Result<FpgaSource> {
+        let mut source = bladerf_fpga_source_BLADERF_FPGA_SOURCE_UNKNOWN;
+        let res = unsafe { bladerf_get_fpga_source(self.device, &mut source) };
+        check_res!(res);
+        FpgaSource::try_from(source)
+    }
+
+    /// Size of the SPI flash in bytes, and whether the size was guessed from the FPGA size
+    /// because the flash could not be identified
+    pub fn get_flash_size(&self) -> Result<(u32, bool)> {
+        let mut size: u32 = 0;
+        let mut is_guess = false;
+        let res = unsafe { bladerf_get_flash_size(self.device, &mut size, &mut is_guess) };
+        check_res!(res);
+        Ok((size, is_guess))
+    }
+
     pub fn firmware_version(&self) -> Result<Version> {
         let mut version = bladerf_version {
             major: 0,
@@ -169,6 +293,122 @@ impl BladeRF {
         Ok(unsafe { Version::from_ffi(&version) })
     }
 
+    /// Check the linked libbladerf, the firmware and the loaded FPGA image against
+    /// [`crate::MIN_LIBBLADERF_VERSION`], [`crate::MIN_FIRMWARE_VERSION`] and
+    /// [`crate::MIN_FPGA_VERSION`].
+    ///
+    /// Outdated components otherwise only show up as [`Error::UpdateFw`] or
+    /// [`Error::UpdateFpga`] from a later call, so this is meant to be called right after opening
+    /// the device. Returns an [`Error::Msg`] listing everything that needs updating.
+    ///
+    /// An FPGA that has not been loaded yet is not an update problem and is skipped, call this
+    /// again after [`BladeRF::load_fpga_path`] to check it too.
+    pub fn check_compatibility(&self) -> Result<()> {
+        use crate::{MIN_FIRMWARE_VERSION, MIN_FPGA_VERSION, MIN_LIBBLADERF_VERSION};
+
+        let mut outdated = Vec::new();
+
+        let lib = crate::version()?;
+        if lib < MIN_LIBBLADERF_VERSION {
+            outdated.push(format!(
+                "libbladerf {lib} (need {MIN_LIBBLADERF_VERSION} or newer)"
+            ));
+        }
+
+        let firmware = self.firmware_version()?;
+        if firmware < MIN_FIRMWARE_VERSION {
+            outdated.push(format!(
+                "firmware {firmware} (need {MIN_FIRMWARE_VERSION} or newer)"
+            ));
+        }
+
+        if self.is_fpga_configured()? {
+            let fpga = self.fpga_version()?;
+            if fpga < MIN_FPGA_VERSION {
+                outdated.push(format!("FPGA {fpga} (need {MIN_FPGA_VERSION} or newer)"));
+            }
+        }
+
+        if outdated.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::msg(format!(
+                "Device needs updating: {}",
+                outdated.join(", ")
+            )))
+        }
+    }
+
+    /// Name of the board, e.g. `bladerf1` or `bladerf2`
+    pub fn get_board_name(&self) -> Result<String> {
+        let name = unsafe { bladerf_get_board_name(self.device) };
+        if name.is_null() {
+            return Err(Error::msg("bladerf_get_board_name returned null pointer"));
+        }
+        // SAFETY: libbladerf returns a static, nul terminated string
+        let name = unsafe { CStr::from_ptr(name) };
+        Ok(name.to_string_lossy().into_owned())
+    }
+
+    /// Board family of the device, see [`BladeRF::get_board_name`]
+    pub fn board_kind(&self) -> Result<BoardKind> {
+        BoardKind::try_from(self.get_board_name()?.as_str())
+    }
+
+    /// Check that this is a `board`, failing with [`Error::Unsupported`] otherwise.
+    ///
+    /// Use this, or branch on [`BladeRF::board_kind`], before calling board specific functions
+    /// instead of relying on libbladerf to reject them.
+    pub fn require_board(&self, board: BoardKind) -> Result<()> {
+        let actual = self.board_kind()?;
+        if actual != board {
+            warn!("Expected a {board}, but the device is a {actual}");
+            return Err(Error::Unsupported);
+        }
+        Ok(())
+    }
+
+    /// Check that `channel` exists on this board, failing with [`Error::Inval`] otherwise.
+    ///
+    /// The bladeRF1 has a single RX and TX channel, [`Channel::Rx0`] and [`Channel::Tx0`], but
+    /// libbladerf accepts [`Channel::Rx1`] and [`Channel::Tx1`] and silently operates on channel
+    /// 0 instead. The bladeRF2 (bladeRF 2.0 micro) has all four channels, see [`Channel::all`].
+    pub fn validate_channel(&self, channel: Channel) -> Result<()> {
+        let board = self.board_kind()?;
+        if !Channel::all(board).contains(&channel) {
+            warn!("Channel {channel} does not exist on {board}");
+            return Err(Error::Inval);
+        }
+        Ok(())
+    }
+
+    /// USB speed the device is operating at
+    pub fn get_device_speed(&self) -> Result<DeviceSpeed> {
+        let speed = unsafe { bladerf_device_speed(self.device) };
+        DeviceSpeed::try_from(speed)
+    }
+
+    /// Gather identification, version and FPGA information in one call
+    pub fn full_info(&self) -> Result<FullDeviceInfo> {
+        let fpga_configured = self.is_fpga_configured()?;
+        let fpga_version = if fpga_configured {
+            Some(self.fpga_version()?)
+        } else {
+            None
+        };
+
+        Ok(FullDeviceInfo {
+            dev_info: self.info()?,
+            board_name: self.get_board_name()?,
+            serial: self.get_serial()?,
+            speed: self.get_device_speed()?,
+            firmware_version: self.firmware_version()?,
+            fpga_configured,
+            fpga_version,
+            fpga_size: self.get_fpga_size_typed()?,
+        })
+    }
+
     // RX & TX Module Control
     // http://www.nuand.com/libbladeRF-doc/v2.5.0/group___f_n___m_o_d_u_l_e.html
 
@@ -180,8 +420,63 @@ impl BladeRF {
         self.set_module_enabled(channel, false)
     }
 
+    /// Enable every channel carried by a stream with the given layout.
+    ///
+    /// libbladerf has no call that enables several channels at once, so they are enabled one
+    /// after the other. In a MIMO layout both channels are sampled from the same RFIC clock and
+    /// interleaved by the FPGA into a single stream, so once both are enabled, samples at the same
+    /// position in the stream are aligned. The first buffers may predate the second channel being
+    /// enabled; discard them with [`BladeRF::sync_rx_drain`]. To start capture at a known time, or
+    /// to align several devices, use timestamps or triggers.
+    pub fn enable_layout(&self, layout: ChannelLayout) -> Result<()> {
+        for &channel in layout.channels() {
+            self.enable_module(channel)?;
+        }
+        Ok(())
+    }
+
+    /// Disable every channel carried by a stream with the given layout
+    pub fn disable_layout(&self, layout: ChannelLayout) -> Result<()> {
+        for &channel in layout.channels() {
+            self.disable_module(channel)?;
+        }
+        Ok(())
+    }
+
+    /// Whether `channel` was enabled through this handle, see [`BladeRF::set_module_enabled`]
+    pub fn is_module_enabled(&self, channel: Channel) -> bool {
+        self.enabled_modules.lock()[channel]
+    }
+
+    /// Enable a module for as long as the returned guard is alive.
+    ///
+    /// Dropping the guard disables the module again only if it was disabled before this call,
+    /// so a guard taken over a running stream leaves it running.
+    pub fn enable_module_guard(&self, channel: Channel) -> Result<ModuleGuard<'_>> {
+        let previous = self.is_module_enabled(channel);
+        self.enable_module(channel)?;
+        Ok(ModuleGuard {
+            device: self,
+            channel,
+            previous,
+        })
+    }
+
+    /// Enable or disable a module
+    ///
+    /// Enabling a module that is already enabled is a no-op, so the running stream is not
+    /// disturbed. Likewise disabling a module that is not enabled does not call into libbladerf.
+    /// The state is tracked per handle, starting with every module disabled.
+    ///
+    /// Disabling a TX module abandons any open TX burst, see [`BladeRF::sync_config_with`].
     pub fn set_module_enabled(&self, channel: Channel, enable: bool) -> Result<()> {
+        if channel.is_tx() && !enable {
+            self.tx_burst_open.store(false, Ordering::Release);
+        }
         let mut enabled_modules = self.enabled_modules.lock();
+        if enabled_modules[channel] == enable {
+            return Ok(());
+        }
 
         let res = unsafe { bladerf_enable_module(self.device, channel as bladerf_channel, enable) };
         check_res!(res);
@@ -205,12 +500,41 @@ impl BladeRF {
         Ok(actual)
     }
 
+    /// Like [`BladeRF::set_sample_rate`], but first checks `rate` against
+    /// [`BladeRF::get_sample_rate_range`], failing with an [`Error::Msg`] naming the valid range
+    /// if it is out of bounds or off-step.
+    ///
+    /// Channels the board does not have fail with [`Error::Inval`], see
+    /// [`BladeRF::validate_channel`].
+    pub fn try_set_sample_rate(&self, channel: Channel, rate: u32) -> Result<u32> {
+        self.validate_channel(channel)?;
+        let range = self.get_sample_rate_range(channel)?;
+        check_in_range("sample rate", channel, rate, &range)?;
+        self.set_sample_rate(channel, rate)
+    }
+
+    /// Set the sample rate of every RX channel at once, returning the actual rate.
+    ///
+    /// RX channels share a single sample clock, so this is equivalent to setting the rate on
+    /// [`Channel::Rx0`].
+    pub fn set_rx_sample_rate(&self, rate: u32) -> Result<u32> {
+        self.set_sample_rate(Channel::Rx0, rate)
+    }
+
+    /// Set the sample rate of every TX channel at once, returning the actual rate.
+    ///
+    /// TX channels share a single sample clock, so this is equivalent to setting the rate on
+    /// [`Channel::Tx0`].
+    pub fn set_tx_sample_rate(&self, rate: u32) -> Result<u32> {
+        self.set_sample_rate(Channel::Tx0, rate)
+    }
+
     pub fn set_rational_sample_rate(
         &self,
         channel: Channel,
-        rate: bladerf_rational_rate,
+        rate: impl Into<bladerf_rational_rate>,
     ) -> Result<RationalRate> {
-        let mut rate = rate;
+        let mut rate = rate.into();
         let mut actual = bladerf_rational_rate {
             integer: 0,
             num: 0,
@@ -266,12 +590,21 @@ impl BladeRF {
         Ok(Range::from(range))
     }
 
+    /// Select the bladeRF1 ADC sampling mode.
+    ///
+    /// With [`Sampling::External`] the RX stream carries samples from the J60/J61 direct sampling
+    /// inputs instead of the LMS6002D, so RX samples no longer reflect the tuned frequency or
+    /// gain. [`BladeRF::sync_config`] logs a warning when an RX stream is configured in this mode.
+    /// Not supported on the bladeRF2, see [`BladeRF::supports_sampling`].
     pub fn set_sampling(&self, sampling: Sampling) -> Result<()> {
         let res = unsafe { bladerf_set_sampling(self.device, sampling as bladerf_sampling) };
         check_res!(res);
         Ok(())
     }
 
+    /// Get the bladeRF1 ADC sampling mode, see [`BladeRF::set_sampling`].
+    ///
+    /// Fails with [`Error::Unsupported`] on the bladeRF2, which has no direct sampling inputs.
     pub fn get_sampling(&self) -> Result<Sampling> {
         let mut sampling = bladerf_sampling_BLADERF_SAMPLING_UNKNOWN;
         let res = unsafe { bladerf_get_sampling(self.device, &mut sampling) };
@@ -279,6 +612,11 @@ impl BladeRF {
         Sampling::try_from(sampling)
     }
 
+    /// Whether the board has a selectable sampling mode, i.e. is a bladeRF1
+    pub fn supports_sampling(&self) -> Result<bool> {
+        Ok(self.board_kind()? == BoardKind::BladeRf1)
+    }
+
     pub fn set_rx_mux(&self, mux: RxMux) -> Result<()> {
         let res = unsafe { bladerf_set_rx_mux(self.device, mux as bladerf_rx_mux) };
         check_res!(res);
@@ -308,6 +646,19 @@ impl BladeRF {
         Ok(actual)
     }
 
+    /// Like [`BladeRF::set_bandwidth`], but first checks `bandwidth` against
+    /// [`BladeRF::get_bandwidth_range`], failing with an [`Error::Msg`] naming the valid range if
+    /// it is out of bounds or off-step.
+    ///
+    /// Channels the board does not have fail with [`Error::Inval`], see
+    /// [`BladeRF::validate_channel`].
+    pub fn try_set_bandwidth(&self, channel: Channel, bandwidth: u32) -> Result<u32> {
+        self.validate_channel(channel)?;
+        let range = self.get_bandwidth_range(channel)?;
+        check_in_range("bandwidth", channel, bandwidth, &range)?;
+        self.set_bandwidth(channel, bandwidth)
+    }
+
     pub fn get_bandwidth(&self, ch: Channel) -> Result<u32> {
         let mut bandwidth: u32 = 0;
         let res =
@@ -351,21 +702,141 @@ impl BladeRF {
         LPFMode::try_from(lpf_mode)
     }
 
+    // RF Ports
+
+    /// Get the names of the RF ports (antenna connections) available on a channel
+    pub fn get_rf_ports(&self, channel: Channel) -> Result<Vec<String>> {
+        // First, call with count = 0 to get the number of ports
+        let num_ports = unsafe {
+            bladerf_get_rf_ports(self.device, channel as bladerf_channel, ptr::null_mut(), 0)
+        };
+        check_res!(num_ports);
+        let num_ports = num_ports as usize;
+        if num_ports == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut ports: Vec<*const c_char> = vec![ptr::null(); num_ports];
+        let res = unsafe {
+            bladerf_get_rf_ports(
+                self.device,
+                channel as bladerf_channel,
+                ports.as_mut_ptr(),
+                num_ports as u32,
+            )
+        };
+        check_res!(res);
+
+        let ports: Vec<_> = ports
+            .into_iter()
+            .flat_map(|ptr| {
+                if ptr.is_null() {
+                    None
+                } else {
+                    unsafe { CStr::from_ptr(ptr).to_str() }
+                        .ok()
+                        .map(ToString::to_string)
+                }
+            })
+            .collect();
+
+        Ok(ports)
+    }
+
+    /// Get the name of the RF port currently selected on a channel
+    pub fn get_rf_port(&self, channel: Channel) -> Result<String> {
+        let mut port: *const c_char = ptr::null();
+        let res =
+            unsafe { bladerf_get_rf_port(self.device, channel as bladerf_channel, &mut port) };
+        check_res!(res);
+        if port.is_null() {
+            return Err(Error::msg("bladerf_get_rf_port returned null pointer"));
+        }
+
+        // SAFETY: non-null, points to a static string owned by libbladerf
+        let port = unsafe { CStr::from_ptr(port) }
+            .to_str()
+            .map_err(|e| Error::msg(format!("RF port name is not UTF-8: {e:?}")))?;
+        Ok(port.to_string())
+    }
+
+    /// Select an RF port by name, as returned by [`BladeRF::get_rf_ports`]
+    pub fn set_rf_port(&self, channel: Channel, port: &str) -> Result<()> {
+        let port_cstr = CString::new(port).map_err(|_| Error::msg("Invalid port string"))?;
+        let res = unsafe {
+            bladerf_set_rf_port(self.device, channel as bladerf_channel, port_cstr.as_ptr())
+        };
+        check_res!(res);
+        Ok(())
+    }
+
     /// Set frequency band
-    pub fn select_band(&self, channel: Channel, frequency: u64) -> Result<()> {
+    pub fn select_band(&self, channel: Channel, frequency: impl Into<Hertz>) -> Result<()> {
+        let frequency = frequency.into().as_hz();
         let res =
             unsafe { bladerf_select_band(self.device, channel as bladerf_channel, frequency) };
         check_res!(res);
         Ok(())
     }
 
-    pub fn set_frequency(&self, channel: Channel, frequency: u64) -> Result<()> {
+    /// Tune `channel` to `frequency`.
+    ///
+    /// On the bladeRF2 all RX channels share one LO and all TX channels another, so tuning
+    /// [`Channel::Rx1`] also retunes [`Channel::Rx0`] and vice versa. See
+    /// [`BladeRF::frequency_is_shared`] and [`BladeRF::set_frequency_shared_aware`].
+    pub fn set_frequency(&self, channel: Channel, frequency: impl Into<Hertz>) -> Result<()> {
+        let frequency = frequency.into().as_hz();
         let res =
             unsafe { bladerf_set_frequency(self.device, channel as bladerf_channel, frequency) };
+        self.invalidate_gain_ranges(channel);
         check_res!(res);
         Ok(())
     }
 
+    /// Whether the channels of `direction` share an LO, so that tuning one retunes all of them.
+    ///
+    /// True on boards with more than one channel per direction, i.e. the bladeRF2.
+    pub fn frequency_is_shared(&self, direction: Direction) -> Result<bool> {
+        let board = self.board_kind()?;
+        let channels = match direction {
+            Direction::RX => Channel::rx_channels(board),
+            Direction::TX => Channel::tx_channels(board),
+        };
+        Ok(channels.len() > 1)
+    }
+
+    /// Like [`BladeRF::set_frequency`], returning the other channels that were retuned along
+    /// with `channel` because they share its LO. Empty on the bladeRF1.
+    pub fn set_frequency_shared_aware(
+        &self,
+        channel: Channel,
+        frequency: impl Into<Hertz>,
+    ) -> Result<Vec<Channel>> {
+        let board = self.board_kind()?;
+        self.set_frequency(channel, frequency)?;
+
+        let (direction, _) = channel.to_direction_index();
+        Ok(Channel::all(board)
+            .iter()
+            .copied()
+            .filter(|&other| other != channel && other.to_direction_index().0 == direction)
+            .collect())
+    }
+
+    /// Like [`BladeRF::set_frequency`], but first checks `frequency` against
+    /// [`BladeRF::get_frequency_range`], failing with an [`Error::Msg`] naming the valid range if
+    /// it is out of bounds or off-step. Use [`Range::snap`] to pick a valid frequency.
+    ///
+    /// Channels the board does not have fail with [`Error::Inval`], see
+    /// [`BladeRF::validate_channel`].
+    pub fn try_set_frequency(&self, channel: Channel, frequency: impl Into<Hertz>) -> Result<()> {
+        self.validate_channel(channel)?;
+        let frequency = frequency.into();
+        let range = self.get_frequency_range(channel)?;
+        check_in_range("frequency", channel, frequency.as_hz(), &range)?;
+        self.set_frequency(channel, frequency)
+    }
+
     pub fn get_frequency(&self, channel: Channel) -> Result<u64> {
         let mut freq: u64 = 0;
         let res =
@@ -389,13 +860,22 @@ impl BladeRF {
         Ok(Range::from(range))
     }
 
+    /// Retune `channel` to `frequency` when the device timestamp of its direction reaches
+    /// `time`, the same counter used by [`Metadata`] timestamps on a metadata stream. A `time`
+    /// of 0 retunes immediately.
+    ///
+    /// Pass a [`QuickTune`] from [`BladeRF::get_quick_tune`] to skip the tuning algorithm and
+    /// retune faster. The device queues a limited number of retunes; when the queue is full this
+    /// fails with [`Error::QueueFull`], and the caller can retry once an earlier scheduled
+    /// timestamp has passed.
     pub fn schedule_retune(
         &self,
         channel: Channel,
         time: u64,
-        frequency: u64,
+        frequency: impl Into<Hertz>,
         quick_tune: Option<&mut QuickTune>,
     ) -> Result<()> {
+        let frequency = frequency.into().as_hz();
         let quick_tune_ptr = quick_tune
             .map(|qt| qt as *mut QuickTune as *mut bladerf_quick_tune)
             .unwrap_or(ptr::null_mut());
@@ -408,10 +888,12 @@ impl BladeRF {
                 quick_tune_ptr,
             )
         };
+        self.invalidate_gain_ranges(channel);
         check_res!(res);
         Ok(())
     }
 
+    /// Cancel every retune scheduled on `channel` that has not happened yet
     pub fn cancel_scheduled_retune(&self, channel: Channel) -> Result<()> {
         let res =
             unsafe { bladerf_cancel_scheduled_retunes(self.device, channel as bladerf_channel) };
@@ -419,14 +901,12 @@ impl BladeRF {
         Ok(())
     }
 
+    /// Capture the current tuning of `channel` for a later [`BladeRF::schedule_retune`].
+    ///
+    /// For frequency hopping, tune to each hop frequency once up front and keep its quick tune.
+    /// See [`QuickTune`] for how many can be stored on the bladeRF2.
     pub fn get_quick_tune(&self, channel: Channel) -> Result<QuickTune> {
-        let mut quick_tune = QuickTune {
-            freqsel: 0,
-            vcocap: 0,
-            nint: 0,
-            nfrac: 0,
-            flags: 0,
-        };
+        let mut quick_tune = QuickTune::default();
         let res = unsafe {
             bladerf_get_quick_tune(
                 self.device,
@@ -472,6 +952,12 @@ impl BladeRF {
         Ok(supported)
     }
 
+    /// Select a loopback mode, or [`Loopback::None`] for normal operation.
+    ///
+    /// The baseband and RF LNA modes only exist on the bladeRF1 and [`Loopback::RficBist`] only
+    /// on the bladeRF2, see [`Loopback::supported_on`]. Unsupported modes fail with an error
+    /// from libbladerf.
+    ///
     /// See: <http://www.nuand.com/libbladeRF-doc/v2.5.0/group___f_n___l_o_o_p_b_a_c_k.html>
     pub fn set_loopback(&self, loopback: Loopback) -> Result<()> {
         let res = unsafe { bladerf_set_loopback(self.device, loopback as bladerf_loopback) };
@@ -491,7 +977,190 @@ impl BladeRF {
         }
     }
 
+    /// Prepare the device for a self-contained TX to RX loopback test.
+    ///
+    /// Disables any enabled modules, routes baseband samples to the RX stream and then selects
+    /// `mode`, which is the order the loopback must be changed in. Re-enable the modules once the
+    /// streams are configured.
+    pub fn enter_loopback_test(&self, mode: Loopback) -> Result<()> {
+        self.disable_enabled_modules()?;
+        self.set_rx_mux(RxMux::Baseband)?;
+        self.set_loopback(mode)
+    }
+
+    /// Leave a loopback test started with [`BladeRF::enter_loopback_test`], disabling any enabled
+    /// modules and restoring normal RF operation.
+    pub fn exit_loopback_test(&self) -> Result<()> {
+        self.disable_enabled_modules()?;
+        self.set_loopback(Loopback::None)
+    }
+
+    /// Send a known waveform through an internal loopback and check that it comes back intact,
+    /// as a health check of the whole sample path.
+    ///
+    /// Uses the first supported mode out of [`Loopback::Firmware`], [`Loopback::RficBist`] and
+    /// [`Loopback::BbTxlpfRxlpf`], failing with [`Error::Unsupported`] if there is none. A PRBS
+    /// modulated QPSK waveform is transmitted repeatedly on [`Channel::Tx0`] and correlated with
+    /// what [`Channel::Rx0`] receives, at the currently configured sample rate.
+    ///
+    /// The synchronous interface is reconfigured for [`Format::Sc16Q11`] and modules enabled
+    /// through this handle are disabled, so configure the streams again afterwards. The loopback
+    /// is reset to [`Loopback::None`] even if the test fails with an error.
+    pub fn self_test_loopback(&self) -> Result<SelfTestReport> {
+        let mut mode = None;
+        for candidate in [
+            Loopback::Firmware,
+            Loopback::RficBist,
+            Loopback::BbTxlpfRxlpf,
+        ] {
+            if self.is_loopback_mode_supported(candidate)? {
+                mode = Some(candidate);
+                break;
+            }
+        }
+        let Some(mode) = mode else {
+            warn!("No loopback mode suitable for a self test is supported");
+            return Err(Error::Unsupported);
+        };
+
+        self.enter_loopback_test(mode)?;
+        let report = self.run_loopback_test(mode);
+        let exit = self.exit_loopback_test();
+        let report = report?;
+        exit?;
+        Ok(report)
+    }
+
+    fn run_loopback_test(&self, mode: Loopback) -> Result<SelfTestReport> {
+        /// Length of the repeated waveform, in samples
+        const PERIOD: usize = 1024;
+        const BUFFER_SIZE: usize = 8 * PERIOD;
+        /// RX buffers discarded while the loopback settles
+        const SETTLE_BUFFERS: usize = 8;
+
+        let timeout = Duration::from_secs(1);
+        let config = StreamConfig::new(16, BUFFER_SIZE as u32, 8, timeout)?;
+        self.sync_config_with(ChannelLayout::RxSISO, Format::Sc16Q11, &config)?;
+        self.sync_config_with(ChannelLayout::TxSISO, Format::Sc16Q11, &config)?;
+        let _rx = self.enable_module_guard(Channel::Rx0)?;
+        let _tx = self.enable_module_guard(Channel::Tx0)?;
+
+        let reference = prbs_qpsk(PERIOD);
+        let tx_buf = reference.repeat(BUFFER_SIZE / PERIOD);
+        let done = AtomicBool::new(false);
+
+        let received = thread::scope(|s| {
+            let tx = s.spawn(|| {
+                while !done.load(Ordering::Acquire) {
+                    self.sync_tx(&tx_buf, None, timeout)?;
+                }
+                Ok::<_, Error>(())
+            });
+
+            let mut rx_buf = vec![Complex::new(0, 0); BUFFER_SIZE];
+            let rx = (0..=SETTLE_BUFFERS)
+                .try_for_each(|_| self.sync_rx(&mut rx_buf, None, timeout))
+                .map(|()| rx_buf);
+
+            done.store(true, Ordering::Release);
+            tx.join().unwrap_or_else(|e| panic::resume_unwind(e))?;
+            rx
+        })?;
+
+        Ok(SelfTestReport::analyze(
+            mode,
+            &reference,
+            &received[BUFFER_SIZE - PERIOD..],
+        ))
+    }
+
+    fn disable_enabled_modules(&self) -> Result<()> {
+        let enabled_modules = *self.enabled_modules.lock();
+        for (channel, enabled) in enabled_modules {
+            if enabled {
+                self.disable_module(channel)?;
+            }
+        }
+        Ok(())
+    }
+
     // SMB Clock Port Control
+
+    /// Frequencies the bladeRF1 SMB clock port can output or accept, from
+    /// [`crate::SMB_FREQUENCY_MIN`] to [`crate::SMB_FREQUENCY_MAX`].
+    ///
+    /// The range has no step, since [`BladeRF::set_rational_smb_frequency`] accepts fractional
+    /// frequencies.
+    pub fn get_smb_frequency_range(&self) -> Range {
+        Range {
+            min: crate::SMB_FREQUENCY_MIN as f64,
+            max: crate::SMB_FREQUENCY_MAX as f64,
+            step: 0.0,
+        }
+    }
+
+    /// Set the bladeRF1 SMB clock port output frequency, returning the actual frequency.
+    ///
+    /// Fails with an [`Error::Msg`] naming the valid range if `rate` is outside
+    /// [`crate::SMB_FREQUENCY_MIN`]..=[`crate::SMB_FREQUENCY_MAX`].
+    pub fn set_smb_frequency(&self, rate: u32) -> Result<u32> {
+        check_smb_frequency(rate as f64)?;
+
+        let mut actual: u32 = 0;
+        let res = unsafe { bladerf_set_smb_frequency(self.device, rate, &mut actual) };
+        check_res!(res);
+        Ok(actual)
+    }
+
+    /// Set the bladeRF1 SMB clock port output frequency with a rational value, returning the
+    /// actual frequency.
+    ///
+    /// Fails with an [`Error::Msg`] naming the valid range if `rate` is outside
+    /// [`crate::SMB_FREQUENCY_MIN`]..=[`crate::SMB_FREQUENCY_MAX`].
+    pub fn set_rational_smb_frequency(
+        &self,
+        rate: impl Into<bladerf_rational_rate>,
+    ) -> Result<RationalRate> {
+        let rate = rate.into();
+        if rate.den == 0 {
+            return Err(Error::msg(
+                "Rational SMB frequency denominator must be non-zero",
+            ));
+        }
+        check_smb_frequency(rate.integer as f64 + rate.num as f64 / rate.den as f64)?;
+
+        let mut rate = rate;
+        let mut actual = bladerf_rational_rate {
+            integer: 0,
+            num: 0,
+            den: 0,
+        };
+        let res =
+            unsafe { bladerf_set_rational_smb_frequency(self.device, &mut rate, &mut actual) };
+        check_res!(res);
+        Ok(actual.into())
+    }
+
+    /// Get the bladeRF1 SMB clock port frequency
+    pub fn get_smb_frequency(&self) -> Result<u32> {
+        let mut rate: u32 = 0;
+        let res = unsafe { bladerf_get_smb_frequency(self.device, &mut rate) };
+        check_res!(res);
+        Ok(rate)
+    }
+
+    /// Get the bladeRF1 SMB clock port frequency as a rational value
+    pub fn get_rational_smb_frequency(&self) -> Result<RationalRate> {
+        let mut rate = bladerf_rational_rate {
+            integer: 0,
+            num: 0,
+            den: 0,
+        };
+        let res = unsafe { bladerf_get_rational_smb_frequency(self.device, &mut rate) };
+        check_res!(res);
+        Ok(rate.into())
+    }
+
     // **Gain Control Functions**
 
     /// Set overall system gain
@@ -509,6 +1178,21 @@ impl BladeRF {
         Ok(gain)
     }
 
+    /// Set overall system gain in dB as an `f64`, checked against [`BladeRF::get_gain_range`].
+    ///
+    /// libbladerf only takes whole dB, so a non-integral `gain_db` fails with an [`Error::Msg`]
+    /// instead of being rounded, as does a gain outside the range.
+    pub fn set_gain_db(&self, channel: Channel, gain_db: f64) -> Result<()> {
+        let range = self.get_gain_range(channel)?;
+        let gain = check_gain_db("gain", channel, gain_db, &range)?;
+        self.set_gain(channel, gain)
+    }
+
+    /// Get overall system gain in dB as an `f64`, see [`BladeRF::set_gain_db`]
+    pub fn get_gain_db(&self, channel: Channel) -> Result<f64> {
+        Ok(self.get_gain(channel)? as f64)
+    }
+
     /// Set gain control mode
     pub fn set_gain_mode(&self, channel: Channel, mode: GainMode) -> Result<()> {
         let res = unsafe {
@@ -550,7 +1234,59 @@ impl BladeRF {
         Ok(gain_modes)
     }
 
-    /// Get range of overall system gain
+    /// Check whether `mode` is available on `channel` without attempting to set it.
+    ///
+    /// The list of modes is fetched once per channel and cached for the lifetime of the device.
+    pub fn channel_supports_gain_mode(&self, channel: Channel, mode: GainMode) -> Result<bool> {
+        let mut gain_modes = self.gain_modes.lock();
+        let modes = match &mut gain_modes[channel] {
+            Some(modes) => modes,
+            cached @ None => cached.insert(
+                self.get_gain_modes(channel)?
+                    .into_iter()
+                    .map(|info| info.mode)
+                    .collect(),
+            ),
+        };
+        Ok(modes.contains(&mode))
+    }
+
+    /// Whether `channel` has automatic gain control.
+    ///
+    /// Only RX channels do, and only when libbladerf lists the automatic ([`GainMode::Default`])
+    /// mode for them, which on the bladeRF1 also depends on the loaded FPGA.
+    pub fn supports_agc(&self, channel: Channel) -> Result<bool> {
+        if !channel.is_rx() {
+            return Ok(false);
+        }
+        self.channel_supports_gain_mode(channel, GainMode::Default)
+    }
+
+    /// Turn automatic gain control on or off, using [`GainMode::Default`] (the board's
+    /// automatic mode) or [`GainMode::Manual`].
+    ///
+    /// Fails with [`Error::Unsupported`] when enabling AGC on a channel without it, see
+    /// [`BladeRF::supports_agc`]. Pick one of the advanced AGC modes with
+    /// [`BladeRF::set_gain_mode`] instead.
+    pub fn set_agc_enabled(&self, channel: Channel, enabled: bool) -> Result<()> {
+        if !enabled {
+            return self.set_gain_mode(channel, GainMode::Manual);
+        }
+        if !self.supports_agc(channel)? {
+            return Err(Error::Unsupported);
+        }
+        self.set_gain_mode(channel, GainMode::Default)
+    }
+
+    /// Whether `channel` is under automatic gain control, in any mode other than manual
+    pub fn agc_enabled(&self, channel: Channel) -> Result<bool> {
+        Ok(self.get_gain_mode(channel)? != GainMode::Manual)
+    }
+
+    /// Get range of overall system gain.
+    ///
+    /// The range depends on the frequency `channel` is tuned to, so query it again after
+    /// retuning, or use [`BladeRF::gain_range_cached`].
     pub fn get_gain_range(&self, channel: Channel) -> Result<Range> {
         let mut range_ptr: *const bladerf_range = ptr::null();
         let res = unsafe {
@@ -564,6 +1300,31 @@ impl BladeRF {
         Ok(Range::from(range))
     }
 
+    /// Like [`BladeRF::get_gain_range`], but cached per channel until the channel is retuned.
+    ///
+    /// The cache is cleared by [`BladeRF::set_frequency`] and [`BladeRF::schedule_retune`] for
+    /// every channel of the same direction, since they may share an LO. Tuning through other
+    /// means, such as another handle to the same device, is not tracked.
+    pub fn gain_range_cached(&self, channel: Channel) -> Result<Range> {
+        let mut gain_ranges = self.gain_ranges.lock();
+        if let Some(range) = &gain_ranges[channel] {
+            return Ok(range.clone());
+        }
+        let range = self.get_gain_range(channel)?;
+        gain_ranges[channel] = Some(range.clone());
+        Ok(range)
+    }
+
+    fn invalidate_gain_ranges(&self, channel: Channel) {
+        let (direction, _) = channel.to_direction_index();
+        let mut gain_ranges = self.gain_ranges.lock();
+        for (other, range) in gain_ranges.iter_mut() {
+            if other.to_direction_index().0 == direction {
+                *range = None;
+            }
+        }
+    }
+
     /// Set the gain for a specific gain stage
     pub fn set_gain_stage(&self, channel: Channel, stage: &str, gain: Gain) -> Result<()> {
         let stage_cstr = CString::new(stage).map_err(|_| Error::msg("Invalid stage string"))?;
@@ -655,22 +1416,88 @@ impl BladeRF {
         Ok(stages)
     }
 
-    // **Trigger Functions**
+    /// Like [`BladeRF::set_gain_stage`], with a typed stage
+    pub fn set_gain_stage_typed(
+        &self,
+        channel: Channel,
+        stage: GainStage,
+        gain: Gain,
+    ) -> Result<()> {
+        self.set_gain_stage(channel, stage.name(), gain)
+    }
 
-    /// Initialize a trigger
-    pub fn trigger_init(&self, channel: Channel, signal: TriggerSignal) -> Result<Trigger> {
-        let mut trigger = bladerf_trigger {
-            channel: 0,
-            role: 0,
-            signal: 0,
-            options: 0,
-        };
-        let res = unsafe {
-            bladerf_trigger_init(
-                self.device,
-                channel as bladerf_channel,
-                signal as bladerf_trigger_signal,
-                &mut trigger as *mut bladerf_trigger,
+    /// Like [`BladeRF::get_gain_stage`], with a typed stage
+    pub fn get_gain_stage_typed(&self, channel: Channel, stage: GainStage) -> Result<Gain> {
+        self.get_gain_stage(channel, stage.name())
+    }
+
+    /// Like [`BladeRF::get_gain_stage_range`], with a typed stage
+    pub fn get_gain_stage_range_typed(&self, channel: Channel, stage: GainStage) -> Result<Range> {
+        self.get_gain_stage_range(channel, stage.name())
+    }
+
+    /// Like [`BladeRF::set_gain_db`], for a single gain stage, checked against
+    /// [`BladeRF::get_gain_stage_range_typed`].
+    ///
+    /// Some stages report finer steps than whole dB, such as the quarter dB steps of the
+    /// bladeRF2 TX `dsa` stage, but libbladerf's gain stage API still only takes whole dB, so
+    /// those steps cannot be reached and non-integral gains are rejected here too.
+    pub fn set_gain_stage_db(
+        &self,
+        channel: Channel,
+        stage: GainStage,
+        gain_db: f64,
+    ) -> Result<()> {
+        let range = self.get_gain_stage_range_typed(channel, stage)?;
+        let param = format!("gain of stage {}", stage.name());
+        let gain = check_gain_db(&param, channel, gain_db, &range)?;
+        self.set_gain_stage_typed(channel, stage, gain)
+    }
+
+    /// Get the gain of `stage` in dB, see [`BladeRF::set_gain_stage_db`]
+    pub fn get_gain_stage_db(&self, channel: Channel, stage: GainStage) -> Result<f64> {
+        Ok(self.get_gain_stage_typed(channel, stage)? as f64)
+    }
+
+    /// Gain stages available on `channel`, see [`BladeRF::get_gain_stages`].
+    ///
+    /// Stages libbladerf reports that are not known to [`GainStage`] are skipped.
+    pub fn get_gain_stages_typed(&self, channel: Channel) -> Result<Vec<GainStage>> {
+        Ok(self
+            .get_gain_stages(channel)?
+            .iter()
+            .filter_map(|name| GainStage::from_name(name))
+            .collect())
+    }
+
+    /// Current gain of every stage on `channel`, keyed by [`GainStage`]
+    ///
+    /// Stages the channel does not have are `None`. Stages libbladerf reports that are not known
+    /// to [`GainStage`] are skipped.
+    pub fn get_gain_stage_map(&self, channel: Channel) -> Result<EnumMap<GainStage, Option<Gain>>> {
+        let mut map = EnumMap::default();
+        for stage in self.get_gain_stages_typed(channel)? {
+            map[stage] = Some(self.get_gain_stage_typed(channel, stage)?);
+        }
+        Ok(map)
+    }
+
+    // **Trigger Functions**
+
+    /// Initialize a trigger
+    pub fn trigger_init(&self, channel: Channel, signal: TriggerSignal) -> Result<Trigger> {
+        let mut trigger = bladerf_trigger {
+            channel: 0,
+            role: 0,
+            signal: 0,
+            options: 0,
+        };
+        let res = unsafe {
+            bladerf_trigger_init(
+                self.device,
+                channel as bladerf_channel,
+                signal as bladerf_trigger_signal,
+                &mut trigger as *mut bladerf_trigger,
             )
         };
         check_res!(res);
@@ -766,14 +1593,202 @@ impl BladeRF {
 
     // Corrections and Calibration
 
-    // Corrections and calibration
+    /// Run the bladeRF1 LMS6002D DC offset calibration on `module`.
+    ///
+    /// Tune the RX and TX frequencies first, and calibrate before starting to stream: the
+    /// calibration reconfigures the transceiver while it runs. Running every [`DcCalModule`] is
+    /// a good starting point before fine tuning with [`BladeRF::set_correction`].
+    pub fn calibrate_dc(&self, module: DcCalModule) -> Result<()> {
+        let res = unsafe { bladerf_calibrate_dc(self.device, module as bladerf_cal_module) };
+        check_res!(res);
+        Ok(())
+    }
 
     // Expansion boards
 
+    /// Attach and enable an expansion board
+    ///
+    /// Available on every board, but the XB-100, XB-200 and XB-300 only fit the bladeRF1: on the
+    /// bladeRF2 libbladerf fails with [`Error::Unsupported`] for anything but
+    /// [`ExpansionModule::None`].
+    pub fn expansion_attach(&self, module: ExpansionModule) -> Result<()> {
+        let res = unsafe { bladerf_expansion_attach(self.device, module as bladerf_xb) };
+        check_res!(res);
+        Ok(())
+    }
+
+    /// Determine which expansion board is attached
+    ///
+    /// Always [`ExpansionModule::None`] on the bladeRF2.
+    pub fn get_attached_expansion(&self) -> Result<ExpansionModule> {
+        let mut module = bladerf_xb_BLADERF_XB_NONE;
+        let res = unsafe { bladerf_expansion_get_attached(self.device, &mut module) };
+        check_res!(res);
+        ExpansionModule::try_from(module)
+    }
+
+    // FPGA configuration GPIO
+
+    /// Read the FPGA configuration GPIO register, see [`crate::config_gpio`] for the fields
+    pub fn config_gpio_read(&self) -> Result<u32> {
+        let mut value = 0;
+        let res = unsafe { bladerf_config_gpio_read(self.device, &mut value) };
+        check_res!(res);
+        Ok(value)
+    }
+
+    /// Write the FPGA configuration GPIO register, see [`crate::config_gpio`] for the fields.
+    ///
+    /// Read the register first and change only the bits of interest.
+    ///
+    /// # Safety
+    /// libbladerf manages this register for band selection, module enables, timestamps and
+    /// streaming formats. Writing it directly can silently break tuning or corrupt streams until
+    /// the settings are reapplied or the device is reset.
+    pub unsafe fn config_gpio_write(&self, value: u32) -> Result<()> {
+        let res = unsafe { bladerf_config_gpio_write(self.device, value) };
+        check_res!(res);
+        Ok(())
+    }
+
     // Expansion IO control
 
+    /// Read the state of the expansion GPIO pins
+    pub fn expansion_gpio_read(&self) -> Result<u32> {
+        let mut value = 0;
+        let res = unsafe { bladerf_expansion_gpio_read(self.device, &mut value) };
+        check_res!(res);
+        Ok(value)
+    }
+
+    /// Write the expansion GPIO pins
+    pub fn expansion_gpio_write(&self, value: u32) -> Result<()> {
+        let res = unsafe { bladerf_expansion_gpio_write(self.device, value) };
+        check_res!(res);
+        Ok(())
+    }
+
+    /// Write only the expansion GPIO pins set in `mask`
+    pub fn expansion_gpio_masked_write(&self, mask: u32, value: u32) -> Result<()> {
+        let res = unsafe { bladerf_expansion_gpio_masked_write(self.device, mask, value) };
+        check_res!(res);
+        Ok(())
+    }
+
+    /// Read the expansion GPIO direction register, a set bit marks the pin as an output
+    pub fn expansion_gpio_dir_read(&self) -> Result<u32> {
+        let mut outputs = 0;
+        let res = unsafe { bladerf_expansion_gpio_dir_read(self.device, &mut outputs) };
+        check_res!(res);
+        Ok(outputs)
+    }
+
+    /// Write the expansion GPIO direction register, a set bit marks the pin as an output
+    pub fn expansion_gpio_dir_write(&self, outputs: u32) -> Result<()> {
+        let res = unsafe { bladerf_expansion_gpio_dir_write(self.device, outputs) };
+        check_res!(res);
+        Ok(())
+    }
+
+    /// Write only the expansion GPIO directions set in `mask`
+    pub fn expansion_gpio_dir_masked_write(&self, mask: u32, outputs: u32) -> Result<()> {
+        let res = unsafe { bladerf_expansion_gpio_dir_masked_write(self.device, mask, outputs) };
+        check_res!(res);
+        Ok(())
+    }
+
     // Miscellaneous
 
+    /// Read a raw AD9361 RFIC register (bladeRF2 only)
+    ///
+    /// `address` is a 10-bit SPI register address (`0x000` to `0x3FF`), as listed in the AD9361
+    /// register map (Analog Devices UG-671). For example `0x037` holds the product ID.
+    ///
+    /// # Safety
+    /// This is a debugging escape hatch. Some registers clear on read or have other side effects,
+    /// which can leave the RFIC in a state libbladerf does not expect.
+    pub unsafe fn get_rfic_register(&self, address: u16) -> Result<u8> {
+        let mut value: u8 = 0;
+        let res = unsafe { bladerf_get_rfic_register(self.device, address, &mut value) };
+        check_res!(res);
+        Ok(value)
+    }
+
+    /// Write a raw AD9361 RFIC register (bladeRF2 only), see [`BladeRF::get_rfic_register`] for
+    /// the address space.
+    ///
+    /// # Safety
+    /// This is a debugging escape hatch. Arbitrary writes bypass libbladerf's view of the RFIC
+    /// configuration and can break tuning, gain control and streaming until the device is reset.
+    pub unsafe fn set_rfic_register(&self, address: u16, value: u8) -> Result<()> {
+        let res = unsafe { bladerf_set_rfic_register(self.device, address, value) };
+        check_res!(res);
+        Ok(())
+    }
+
+    /// Read the bladeRF2 AD9361 RFIC temperature in degrees Celsius
+    pub fn get_rfic_temperature(&self) -> Result<f32> {
+        let mut value: f32 = 0.0;
+        let res = unsafe { bladerf_get_rfic_temperature(self.device, &mut value) };
+        check_res!(res);
+        Ok(value)
+    }
+
+    /// Read a bladeRF2 power monitor measurement, in the units documented on [`PmicRegister`].
+    ///
+    /// Fails for the raw registers, see [`BladeRF::get_pmic_register_raw`].
+    pub fn get_pmic_register(&self, reg: PmicRegister) -> Result<f32> {
+        if reg.is_raw() {
+            return Err(Error::msg(format!(
+                "{reg:?} is a raw register, use get_pmic_register_raw"
+            )));
+        }
+        let mut value: f32 = 0.0;
+        let res = unsafe {
+            bladerf_get_pmic_register(
+                self.device,
+                reg as bladerf_pmic_register,
+                (&mut value as *mut f32).cast(),
+            )
+        };
+        check_res!(res);
+        Ok(value)
+    }
+
+    /// Read a raw 16-bit bladeRF2 power monitor register
+    ///
+    /// Fails for the measurement registers, see [`BladeRF::get_pmic_register`].
+    pub fn get_pmic_register_raw(&self, reg: PmicRegister) -> Result<u16> {
+        if !reg.is_raw() {
+            return Err(Error::msg(format!(
+                "{reg:?} is a measurement, use get_pmic_register"
+            )));
+        }
+        let mut value: u16 = 0;
+        let res = unsafe {
+            bladerf_get_pmic_register(
+                self.device,
+                reg as bladerf_pmic_register,
+                (&mut value as *mut u16).cast(),
+            )
+        };
+        check_res!(res);
+        Ok(value)
+    }
+
+    /// Read every bladeRF2 power monitor measurement.
+    ///
+    /// libbladerf reads the registers one at a time, so the values may come from consecutive
+    /// conversions of the INA219 rather than a single instant.
+    pub fn get_power_monitoring(&self) -> Result<PowerMonitor> {
+        Ok(PowerMonitor {
+            voltage_bus: self.get_pmic_register(PmicRegister::VoltageBus)?,
+            voltage_shunt: self.get_pmic_register(PmicRegister::VoltageShunt)?,
+            power: self.get_pmic_register(PmicRegister::Power)?,
+            current: self.get_pmic_register(PmicRegister::Current)?,
+        })
+    }
+
     // Sample formats and metadata
     pub fn abc() {}
 
@@ -782,38 +1797,139 @@ impl BladeRF {
     // Synchronous data transmission and reception
 
     /// Configure the device for synchronous data transfer
+    ///
+    /// `samples_per_buffer` must be a multiple of 1024. See [`StreamConfig`] for guidance on
+    /// choosing the buffer parameters.
     pub fn sync_config(
         &self,
         channel: ChannelLayout,
         format: Format,
         num_buffers: u32,
-        buffer_size: u32,
+        samples_per_buffer: u32,
         num_transfers: u32,
         stream_timeout: Duration,
     ) -> Result<()> {
-        let stream_timeout_ms = stream_timeout.as_millis() as u32;
+        let config = StreamConfig::new(
+            num_buffers,
+            samples_per_buffer,
+            num_transfers,
+            stream_timeout,
+        )?;
+        self.sync_config_with(channel, format, &config)
+    }
+
+    /// Configure the device for synchronous data transfer from a validated [`StreamConfig`]
+    ///
+    /// libbladerf tears down the stream and reallocates its buffers on every call, even when
+    /// only the format changes, as it has no way to change the format of a configured stream.
+    /// See [`BladeRF::sync_reconfigure_format`] to switch formats with the same buffer
+    /// parameters.
+    ///
+    /// Reconfiguring TX fails with an [`Error::Msg`] while a burst started with
+    /// `BLADERF_META_FLAG_TX_BURST_START` has not been closed with `BLADERF_META_FLAG_TX_BURST_END`,
+    /// since tearing down the stream would truncate it. A burst counts as closed once a
+    /// `BURST_END` transfer has been attempted, even if it failed, or once the TX modules are
+    /// disabled, so a broken TX stream can always be recovered by reconfiguring it.
+    pub fn sync_config_with(
+        &self,
+        channel: ChannelLayout,
+        format: Format,
+        config: &StreamConfig,
+    ) -> Result<()> {
+        if channel.is_tx() && self.tx_burst_open.load(Ordering::Acquire) {
+            return Err(Error::msg(
+                "Cannot reconfigure TX while a burst started with BURST_START is open, end it \
+                 with BURST_END or disable the TX module first",
+            ));
+        }
+
+        let stream_timeout_ms = config.timeout().as_millis() as u32;
         let res = unsafe {
             bladerf_sync_config(
                 self.device,
                 // Bindgen not precise with #define types
                 channel as bladerf_channel_layout,
                 format as bladerf_format,
-                num_buffers,
-                buffer_size,
-                num_transfers,
+                config.num_buffers(),
+                config.samples_per_buffer(),
+                config.num_transfers(),
                 stream_timeout_ms,
             )
         };
         check_res!(res);
 
+        self.warn_on_usb2_highrate(channel, format);
+        if channel.is_rx() && matches!(self.get_sampling(), Ok(Sampling::External)) {
+            warn!(
+                "RX stream configured while external sampling is selected, samples will come \
+                 from the direct sampling inputs"
+            );
+        }
+
         // Store the configured format
         let mut fmt = self.format_sync.write().unwrap();
         *fmt = Some(format);
+        self.stream_configs.lock()[channel] = Some((format, *config));
 
         Ok(())
     }
 
+    /// Switch `layout` to `format`, keeping the [`StreamConfig`] it was last configured with.
+    ///
+    /// When `format` is already configured, this returns without touching the stream, so
+    /// libbladerf's buffers are kept. Otherwise the stream is reconfigured and its buffers
+    /// reallocated, see [`BladeRF::sync_config_with`]. Fails with [`Error::Msg`] if `layout` has
+    /// not been configured through this handle yet.
+    pub fn sync_reconfigure_format(&self, layout: ChannelLayout, format: Format) -> Result<()> {
+        let current = self.stream_configs.lock()[layout];
+        let Some((current_format, config)) = current else {
+            return Err(Error::msg(format!(
+                "{layout:?} must be configured with sync_config before changing its format"
+            )));
+        };
+        if current_format == format {
+            return Ok(());
+        }
+        self.sync_config_with(layout, format, &config)
+    }
+
+    /// Log a warning if the current sample rate exceeds what a USB 2.0 link can carry.
+    ///
+    /// This only sees the sample rate set before the stream was configured.
+    fn warn_on_usb2_highrate(&self, layout: ChannelLayout, format: Format) {
+        /// Practical sustained throughput of a USB 2.0 Hi-Speed link
+        const USB2_MAX_BYTES_PER_SEC: u64 = 40_000_000;
+
+        if !matches!(self.get_device_speed(), Ok(DeviceSpeed::HighSpeed)) {
+            return;
+        }
+        let channel = if layout.is_rx() {
+            Channel::Rx0
+        } else {
+            Channel::Tx0
+        };
+        let Ok(rate) = self.get_sample_rate(channel) else {
+            return;
+        };
+
+        let bytes_per_sample = format.sample_size() as u64;
+        let channels = if layout.is_mimo() { 2 } else { 1 };
+        let throughput = rate as u64 * bytes_per_sample * channels;
+        if throughput > USB2_MAX_BYTES_PER_SEC {
+            warn!(
+                "{layout:?} at {rate} Hz needs {throughput} B/s, but the device is connected over \
+                 USB 2.0 which sustains about {USB2_MAX_BYTES_PER_SEC} B/s; expect overruns or \
+                 underruns"
+            );
+        }
+    }
+
     /// Transmit IQ samples synchronously
+    ///
+    /// With a metadata format ([`Format::Sc16Q11Meta`]) pass a [`Metadata`] to schedule the
+    /// samples: e.g. `Metadata::with_timestamp(t).burst_start().burst_end()` transmits a single
+    /// burst at timestamp `t`, and `Metadata::new().burst_start().tx_now()` starts one
+    /// immediately. A timestamp that has already passed fails with [`Error::TimePast`].
     pub fn sync_tx<T>(
         &self,
         data: &[T],
@@ -843,6 +1959,7 @@ impl BladeRF {
         } else {
             std::ptr::null_mut()
         };
+        let flags = bladerf_meta.flags;
 
         let res = unsafe {
             bladerf_sync_tx(
@@ -860,11 +1977,36 @@ impl BladeRF {
             }
         }
 
+        // The burst is over once its end has been attempted, whether or not the transfer worked,
+        // so that a failed burst does not block reconfiguring the stream
+        if flags & BLADERF_META_FLAG_TX_BURST_END != 0 {
+            self.tx_burst_open.store(false, Ordering::Release);
+        }
+
         check_res!(res);
+        self.tx_stats.record(data.len(), mem::size_of_val(data));
+
+        if flags & BLADERF_META_FLAG_TX_BURST_END == 0
+            && flags & BLADERF_META_FLAG_TX_BURST_START != 0
+        {
+            self.tx_burst_open.store(true, Ordering::Release);
+        }
+
+        if !meta_ptr.is_null() && bladerf_meta.status & BLADERF_META_STATUS_UNDERRUN != 0 {
+            self.underruns.fetch_add(1, Ordering::Relaxed);
+            warn!(
+                "TX underrun reported at timestamp {}",
+                bladerf_meta.timestamp
+            );
+        }
+
         Ok(())
     }
 
     /// Receive IQ samples synchronously
+    ///
+    /// With a metadata format, pass a [`Metadata`] to learn the timestamp of the first sample and
+    /// whether samples were dropped before it, see [`Metadata::overrun`] and [`BladeRF::overruns`].
     pub fn sync_rx<T>(
         &self,
         data: &mut [T],
@@ -912,10 +2054,120 @@ impl BladeRF {
         }
 
         check_res!(res);
+        self.rx_stats.record(data.len(), mem::size_of_val(data));
+
+        if !meta_ptr.is_null() && bladerf_meta.status & BLADERF_META_STATUS_OVERRUN != 0 {
+            self.overruns.fetch_add(1, Ordering::Relaxed);
+            warn!(
+                "RX overrun reported at timestamp {}",
+                bladerf_meta.timestamp
+            );
+        }
         Ok(())
     }
 
+    /// Number of RX overruns reported since the device was opened.
+    ///
+    /// Overruns are only visible through metadata, so this counts [`BladeRF::sync_rx`] calls made
+    /// with a [`Metadata`] on a metadata format whose status reported one. Streams without
+    /// metadata drop samples silently.
+    pub fn overruns(&self) -> u64 {
+        self.overruns.load(Ordering::Relaxed)
+    }
+
+    /// Number of TX underruns reported since the device was opened.
+    ///
+    /// Like [`BladeRF::overruns`], only [`BladeRF::sync_tx`] calls made with a [`Metadata`] can
+    /// observe underruns.
+    pub fn underruns(&self) -> u64 {
+        self.underruns.load(Ordering::Relaxed)
+    }
+
+    /// Sample and byte counters of [`BladeRF::sync_rx`]
+    pub fn rx_stats(&self) -> &StreamStats {
+        &self.rx_stats
+    }
+
+    /// Sample and byte counters of [`BladeRF::sync_tx`]
+    pub fn tx_stats(&self) -> &StreamStats {
+        &self.tx_stats
+    }
+
+    /// Receive IQ samples synchronously, converted to floating point with [`brf_ci16_slice_to_cf32`].
+    ///
+    /// The stream must be configured with [`Format::Sc16Q11`] or [`Format::Sc16Q11Meta`]. Samples
+    /// are read into a scratch buffer owned by the handle, which is reused across calls.
+    pub fn sync_rx_cf32(
+        &self,
+        data: &mut [Complex<f32>],
+        metadata: Option<&mut Metadata>,
+        timeout: Duration,
+    ) -> Result<()> {
+        let mut scratch = self.rx_scratch.lock();
+        scratch.resize(data.len(), Complex::new(0, 0));
+        self.sync_rx(&mut scratch[..], metadata, timeout)?;
+
+        brf_ci16_slice_to_cf32(&scratch, data);
+        Ok(())
+    }
+
+    /// Read and discard samples that were buffered before the caller started receiving, returning
+    /// the number of samples dropped.
+    ///
+    /// Samples are read in blocks until one has to wait on the hardware rather than being served
+    /// from the USB/FPGA buffers, or until a read times out. The next [`BladeRF::sync_rx`] then
+    /// returns fresh data.
+    pub fn sync_rx_drain(&self, timeout: Duration) -> Result<usize> {
+        let format = self
+            .format_sync
+            .read()
+            .unwrap()
+            .ok_or_else(|| Error::msg("Format not configured"))?;
+
+        match format {
+            Format::Sc16Q11 | Format::Sc16Q11Meta => {
+                self.sync_rx_drain_as::<Complex<i16>>(format, timeout)
+            }
+            Format::Sc8Q7 | Format::Sc8Q7Meta => {
+                self.sync_rx_drain_as::<Complex<i8>>(format, timeout)
+            }
+        }
+    }
+
+    fn sync_rx_drain_as<T>(&self, format: Format, timeout: Duration) -> Result<usize>
+    where
+        T: SampleFormat + Default + Clone,
+    {
+        const DRAIN_BLOCK_SAMPLES: usize = 4096;
+
+        let rate = self.get_sample_rate(Channel::Rx0)?;
+        // A block that arrives in less than half its real-time duration was already buffered
+        let live_threshold =
+            Duration::from_secs_f64(DRAIN_BLOCK_SAMPLES as f64 / rate.max(1) as f64 / 2.0);
+
+        let mut scratch = vec![T::default(); DRAIN_BLOCK_SAMPLES];
+        let mut drained = 0;
+        loop {
+            let start = Instant::now();
+            // Metadata formats require metadata on every transfer
+            let mut meta = Metadata::new().rx_now();
+            let meta = format.is_meta().then_some(&mut meta);
+            match self.sync_rx(&mut scratch, meta, timeout) {
+                Ok(()) => drained += scratch.len(),
+                Err(Error::Timeout) => return Ok(drained),
+                Err(e) => return Err(e),
+            }
+            if start.elapsed() >= live_threshold {
+                return Ok(drained);
+            }
+        }
+    }
+
     /// Retrieve the current timestamp
+    ///
+    /// The value is read over a control transfer, so it is already stale by the time it is
+    /// returned: expect it to lag the device counter by a few hundred microseconds to
+    /// milliseconds worth of samples, depending on the USB link and host load.
     pub fn get_timestamp(&self, dir: Direction) -> Result<u64> {
         let mut timestamp: u64 = 0;
         let res = unsafe { bladerf_get_timestamp(self.device, dir.into(), &mut timestamp) };
@@ -923,6 +2175,27 @@ impl BladeRF {
         Ok(timestamp)
     }
 
+    /// Retrieve the current RX and TX timestamps, in that order.
+    ///
+    /// The two values are read back to back, so they are only as aligned as two
+    /// consecutive control transfers allow.
+    pub fn get_timestamps(&self) -> Result<(u64, u64)> {
+        let rx = self.get_timestamp(Direction::RX)?;
+        let tx = self.get_timestamp(Direction::TX)?;
+        Ok((rx, tx))
+    }
+
+    /// Metadata for a TX transfer scheduled `offset_samples` samples after the current TX
+    /// timestamp.
+    ///
+    /// The offset must cover the latency of [`BladeRF::get_timestamp`] and of queuing the samples,
+    /// otherwise the transfer fails with [`Error::TimePast`]. Add the burst flags as needed, e.g.
+    /// `device.schedule_tx_at(offset)?.burst_start().burst_end()` for a single-buffer burst.
+    pub fn schedule_tx_at(&self, offset_samples: u64) -> Result<Metadata> {
+        let now = self.get_timestamp(Direction::TX)?;
+        Ok(Metadata::with_timestamp(now + offset_samples))
+    }
+
     // Device loading and programming
 
     /// Write FX3 firmware to the bladeRF’s SPI flash
@@ -943,6 +2216,40 @@ impl BladeRF {
         Ok(())
     }
 
+    /// Reset the device and open it again once it has re-enumerated, returning the new handle.
+    ///
+    /// The device is found again by its serial number, polling the device list until `timeout`
+    /// expires, after which this fails with [`Error::Timeout`]. Errors from the reset itself are
+    /// only logged. Resetting is the usual way out
+    /// of a device stuck after a failed calibration (e.g. an RFIC calibration timeout).
+    pub fn reopen_after_reset(self, timeout: Duration) -> Result<Self> {
+        const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+        let serial = self.info()?.serial();
+        // The reset tears down the USB device under the control transfer, so it commonly reports
+        // an error even when it worked. Only failing to find the device again counts. Resetting
+        // consumes and closes this handle before the new one is opened.
+        if let Err(e) = self.device_reset() {
+            warn!("Device {serial} reported an error while resetting: {e:?}");
+        }
+
+        let start = Instant::now();
+        loop {
+            for info in crate::get_device_list().unwrap_or_default() {
+                if info.serial() == serial {
+                    if let Ok(device) = info.open() {
+                        return Ok(device);
+                    }
+                }
+            }
+            if start.elapsed() > timeout {
+                warn!("Device {serial} did not reappear within {timeout:?} of resetting");
+                return Err(Error::Timeout);
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
     /// Uploads the fpga bitstream file from the path in env var [`FPGA_BITSTREAM_VAR_NAME`].
     pub fn load_fpga_from_env(&self) -> Result<()> {
         let path = std::env::var(FPGA_BITSTREAM_VAR_NAME).map_err(|e| {
@@ -954,6 +2261,10 @@ impl BladeRF {
         self.load_fpga_path(Path::new(&path))
     }
 
+    /// Load an FPGA bitstream file into the FPGA.
+    ///
+    /// The bitstream is lost when the device is power cycled, see [`BladeRF::flash_fpga`] to
+    /// have it loaded automatically on power up.
     pub fn load_fpga_path(&self, bitstream_path: impl AsRef<Path>) -> Result<()> {
         let bitstream_path = CString::new(bitstream_path.as_ref().as_os_str().as_encoded_bytes())
             .map_err(|e| Error::msg(format!("Invalid path for cstring: {e:?}")))?;
@@ -963,6 +2274,22 @@ impl BladeRF {
         Ok(())
     }
 
+    /// Load an FPGA bitstream held in memory, e.g. embedded with `include_bytes!`.
+    ///
+    /// libbladerf only loads bitstreams from files, so `bitstream` is written to a temporary file
+    /// that is removed again once loading finishes or fails. As with
+    /// [`BladeRF::load_fpga_path`], the bitstream is lost when the device is power cycled.
+    pub fn load_fpga_from_bytes(&self, bitstream: &[u8]) -> Result<()> {
+        let mut file = tempfile::Builder::new()
+            .prefix("bladerf-fpga")
+            .suffix(".rbf")
+            .tempfile()?;
+        file.write_all(bitstream)?;
+        file.flush()?;
+
+        self.load_fpga_path(file.path())
+    }
+
     pub fn flash_fpga(&self, bitstream_path: impl AsRef<Path>) -> Result<()> {
         let bitstream_path = CString::new(bitstream_path.as_ref().as_os_str().as_encoded_bytes())
             .map_err(|e| Error::msg(format!("Invalid path for cstring: {e:?}")))?;
@@ -972,12 +2299,82 @@ impl BladeRF {
         Ok(())
     }
 
+    /// Read `count` pages of [`crate::FLASH_PAGE_SIZE`] bytes from the SPI flash, starting at
+    /// page `page`
+    pub fn read_flash(&self, page: u32, count: u32) -> Result<Vec<u8>> {
+        self.check_flash_range(page, count, crate::FLASH_PAGE_SIZE)?;
+
+        let mut buf = vec![0u8; (count * crate::FLASH_PAGE_SIZE) as usize];
+        let res = unsafe { bladerf_read_flash(self.device, buf.as_mut_ptr(), page, count) };
+        check_res!(res);
+        Ok(buf)
+    }
+
+    /// Write `data` to the SPI flash starting at page `page`. The pages must have been erased
+    /// with [`BladeRF::erase_flash`] first.
+    ///
+    /// Fails with [`Error::Misaligned`] unless `data` is a whole number of
+    /// [`crate::FLASH_PAGE_SIZE`] pages.
+    ///
+    /// # Safety
+    /// The flash holds the FX3 firmware, the calibration data and the autoloaded FPGA bitstream.
+    /// Writing over them can leave the device unable to boot until it is recovered over the FX3
+    /// bootloader.
+    pub unsafe fn write_flash(&self, page: u32, data: &[u8]) -> Result<()> {
+        let len = u32::try_from(data.len()).map_err(|_| {
+            Error::msg(format!(
+                "Flash write of {} bytes is larger than any flash",
+                data.len()
+            ))
+        })?;
+        if !len.is_multiple_of(crate::FLASH_PAGE_SIZE) {
+            warn!(
+                "Flash write of {} bytes is not a multiple of the {} byte page size",
+                data.len(),
+                crate::FLASH_PAGE_SIZE
+            );
+            return Err(Error::Misaligned);
+        }
+        let count = len / crate::FLASH_PAGE_SIZE;
+        self.check_flash_range(page, count, crate::FLASH_PAGE_SIZE)?;
+
+        let res = unsafe { bladerf_write_flash(self.device, data.as_ptr(), page, count) };
+        check_res!(res);
+        Ok(())
+    }
+
+    /// Erase `count` erase blocks of [`crate::FLASH_ERASE_BLOCK_SIZE`] bytes, starting at erase
+    /// block `erase_block`
+    ///
+    /// # Safety
+    /// See [`BladeRF::write_flash`]: erasing the firmware region leaves the device unable to
+    /// boot.
+    pub unsafe fn erase_flash(&self, erase_block: u32, count: u32) -> Result<()> {
+        self.check_flash_range(erase_block, count, crate::FLASH_ERASE_BLOCK_SIZE)?;
+
+        let res = unsafe { bladerf_erase_flash(self.device, erase_block, count) };
+        check_res!(res);
+        Ok(())
+    }
+
+    /// Check that `count` units of `unit_size` bytes starting at unit `start` fit in the flash
+    fn check_flash_range(&self, start: u32, count: u32, unit_size: u32) -> Result<()> {
+        let (flash_size, _) = self.get_flash_size()?;
+        let end = (start as u64 + count as u64) * unit_size as u64;
+        if end > flash_size as u64 {
+            warn!("Flash access up to byte {end} is past the end of the {flash_size} byte flash");
+            return Err(Error::Range);
+        }
+        Ok(())
+    }
+
     pub fn erase_stored_fpga(&self) -> Result<()> {
         let res = unsafe { bladerf_erase_stored_fpga(self.device) };
         check_res!(res);
         Ok(())
     }
 
+    /// Write the firmware log to the file at `path`
     pub fn get_fw_log(&self, path: impl AsRef<Path>) -> Result<()> {
         let log_path = CString::new(path.as_ref().as_os_str().as_encoded_bytes())
             .map_err(|e| Error::msg(format!("Invalid path for cstring: {e:?}")))?;
@@ -986,8 +2383,102 @@ impl BladeRF {
         Ok(())
     }
 
+    /// Print the firmware log to stdout
+    pub fn get_fw_log_stdout(&self) -> Result<()> {
+        let res = unsafe { bladerf_get_fw_log(self.device, ptr::null()) };
+        check_res!(res);
+        Ok(())
+    }
+
+    // **VCTCXO Trim**
+
+    /// Get the factory VCTCXO trim DAC value stored in flash.
+    ///
+    /// This is the value the trim DAC is set to at power up. It is a raw DAC code: the full
+    /// `u16` range on the bladeRF1, while the bladeRF2 trim DAC is 12 bits wide (0 to 4095).
+    pub fn get_vctcxo_trim(&self) -> Result<u16> {
+        let mut trim: u16 = 0;
+        let res = unsafe { bladerf_get_vctcxo_trim(self.device, &mut trim) };
+        check_res!(res);
+        Ok(trim)
+    }
+
+    /// Select the reference the VCTCXO tamer disciplines the oscillator to.
+    ///
+    /// Only supported on the bladeRF1 with a suitable FPGA. The reference is fed in on the J71
+    /// header.
+    pub fn set_vctcxo_tamer_mode(&self, mode: VctcxoTamerMode) -> Result<()> {
+        let res = unsafe {
+            bladerf_set_vctcxo_tamer_mode(self.device, mode as bladerf_vctcxo_tamer_mode)
+        };
+        check_res!(res);
+        Ok(())
+    }
+
+    pub fn get_vctcxo_tamer_mode(&self) -> Result<VctcxoTamerMode> {
+        let mut mode = bladerf_vctcxo_tamer_mode_BLADERF_VCTCXO_TAMER_INVALID;
+        let res = unsafe { bladerf_get_vctcxo_tamer_mode(self.device, &mut mode) };
+        check_res!(res);
+        VctcxoTamerMode::try_from(mode)
+    }
+
+    /// Read the value currently applied to the VCTCXO trim DAC
+    pub fn trim_dac_read(&self) -> Result<u16> {
+        let mut value: u16 = 0;
+        let res = unsafe { bladerf_trim_dac_read(self.device, &mut value) };
+        check_res!(res);
+        Ok(value)
+    }
+
+    /// Write a raw code to the VCTCXO trim DAC, see [`BladeRF::get_vctcxo_trim`] for the range.
+    ///
+    /// Higher codes raise the oscillator frequency. The value is not persisted and reverts to the
+    /// factory trim on power up. Do not combine this with the VCTCXO tamer, which drives the DAC
+    /// itself.
+    pub fn trim_dac_write(&self, value: u16) -> Result<()> {
+        let res = unsafe { bladerf_trim_dac_write(self.device, value) };
+        check_res!(res);
+        Ok(())
+    }
+
+    // **Clock Source**
+
+    /// Select the bladeRF2 system clock source.
+    ///
+    /// Only switch to [`ClockSelect::External`] with a valid 38.4 MHz clock present on the clock
+    /// input: without one the RFIC and FPGA lose their clock, and streaming and tuning fail until
+    /// the onboard clock is selected again. To share a clock between devices, enable
+    /// [`BladeRF::set_clock_output`] on one and select the external clock on the others.
+    pub fn set_clock_select(&self, select: ClockSelect) -> Result<()> {
+        let res = unsafe { bladerf_set_clock_select(self.device, select as bladerf_clock_select) };
+        check_res!(res);
+        Ok(())
+    }
+
+    pub fn get_clock_select(&self) -> Result<ClockSelect> {
+        let mut select = bladerf_clock_select_CLOCK_SELECT_ONBOARD;
+        let res = unsafe { bladerf_get_clock_select(self.device, &mut select) };
+        check_res!(res);
+        ClockSelect::try_from(select)
+    }
+
+    /// Drive the bladeRF2 system clock out of the clock output connector
+    pub fn set_clock_output(&self, enable: bool) -> Result<()> {
+        let res = unsafe { bladerf_set_clock_output(self.device, enable) };
+        check_res!(res);
+        Ok(())
+    }
+
+    pub fn get_clock_output(&self) -> Result<bool> {
+        let mut enabled = false;
+        let res = unsafe { bladerf_get_clock_output(self.device, &mut enabled) };
+        check_res!(res);
+        Ok(enabled)
+    }
+
     // **Bias Tee Control**
 
+    /// Get whether the bias tee of a channel is enabled, see [`BladeRF::set_bias_tee`]
     pub fn get_bias_tee(&self, channel: Channel) -> Result<bool> {
         let mut enable = false;
         let res =
@@ -996,21 +2487,205 @@ impl BladeRF {
         Ok(enable)
     }
 
+    /// Enable or disable the bias tee (DC power on the RF port) of a channel, e.g. to power an
+    /// active antenna or LNA.
+    ///
+    /// The bladeRF2 has a bias tee on every RX and TX port, so all four channels are supported.
+    /// The bladeRF1 has none, and fails with [`Error::Unsupported`].
+    ///
+    /// Bias tees enabled through this handle are disabled again when it is dropped.
     pub fn set_bias_tee(&self, channel: Channel, enable: bool) -> Result<()> {
+        let mut bias_tees = self.bias_tees.lock();
+
         let res = unsafe { bladerf_set_bias_tee(self.device, channel as bladerf_channel, enable) };
         check_res!(res);
+
+        bias_tees[channel] = enable;
         Ok(())
     }
 
+    /// Set the bias tee of `channel` until the returned guard is dropped, which restores the
+    /// state it had before this call.
+    pub fn bias_tee_guard(&self, channel: Channel, enable: bool) -> Result<BiasTeeGuard<'_>> {
+        let previous = self.get_bias_tee(channel)?;
+        self.set_bias_tee(channel, enable)?;
+        Ok(BiasTeeGuard {
+            device: self,
+            channel,
+            previous,
+        })
+    }
+
     // Higher level control of one RF module
-    pub fn configure_module(&self, channel: Channel, config: ModuleConfig) -> Result<()> {
-        self.set_frequency(channel, config.frequency)?;
-        self.set_sample_rate(channel, config.sample_rate)?;
-        self.set_bandwidth(channel, config.bandwidth)?;
-        self.set_gain(channel, config.gain)?;
 
+    /// Apply frequency, sample rate, bandwidth and gain to `channel`, in that order.
+    ///
+    /// Stops at the first setting that fails, returning an [`Error::Msg`] naming the setting,
+    /// the requested value, the underlying error and, where available, the valid range.
+    pub fn configure_module(&self, channel: Channel, config: ModuleConfig) -> Result<()> {
+        self.apply_module_config(channel, &config, false)?;
         Ok(())
     }
+
+    /// Like [`BladeRF::configure_module`], but first checks `config` with
+    /// [`ModuleConfig::validate`] and returns the values the device actually settled on.
+    ///
+    /// The gain is checked against the gain range once the frequency has been applied, as the
+    /// range depends on it, so an out of range gain fails with the channel already retuned.
+    pub fn configure_module_checked(
+        &self,
+        channel: Channel,
+        config: ModuleConfig,
+    ) -> Result<AppliedModuleConfig> {
+        config.validate(self, channel)?;
+        self.apply_module_config(channel, &config, true)
+    }
+
+    fn apply_module_config(
+        &self,
+        channel: Channel,
+        config: &ModuleConfig,
+        validate_gain: bool,
+    ) -> Result<AppliedModuleConfig> {
+        let context = |param: &str, value: String, range: Result<Range>, e: Error| {
+            let range = match range {
+                Ok(range) => format!(", valid range {range}"),
+                Err(_) => String::new(),
+            };
+            Error::msg(format!(
+                "Failed to set {param} {value} on {channel}: {e}{range}"
+            ))
+        };
+
+        self.set_frequency(channel, config.frequency).map_err(|e| {
+            let range = self.get_frequency_range(channel);
+            context("frequency", format!("{} Hz", config.frequency), range, e)
+        })?;
+        if validate_gain {
+            config.validate_gain(self, channel)?;
+        }
+        let sample_rate = self
+            .set_sample_rate(channel, config.sample_rate)
+            .map_err(|e| {
+                let range = self.get_sample_rate_range(channel);
+                context(
+                    "sample rate",
+                    format!("{} Hz", config.sample_rate),
+                    range,
+                    e,
+                )
+            })?;
+        let bandwidth = self.set_bandwidth(channel, config.bandwidth).map_err(|e| {
+            let range = self.get_bandwidth_range(channel);
+            context("bandwidth", format!("{} Hz", config.bandwidth), range, e)
+        })?;
+        self.set_gain(channel, config.gain).map_err(|e| {
+            let range = self.get_gain_range(channel);
+            context("gain", format!("{} dB", config.gain), range, e)
+        })?;
+
+        Ok(AppliedModuleConfig {
+            frequency: self.get_frequency(channel)?,
+            sample_rate,
+            bandwidth,
+            gain: self.get_gain(channel)?,
+        })
+    }
+}
+
+/// Keeps a module enabled until dropped, then restores its previous state, see
+/// [`BladeRF::enable_module_guard`]
+pub struct ModuleGuard<'a> {
+    device: &'a BladeRF,
+    channel: Channel,
+    previous: bool,
+}
+
+impl ModuleGuard<'_> {
+    pub fn channel(&self) -> Channel {
+        self.channel
+    }
+}
+
+impl Drop for ModuleGuard<'_> {
+    fn drop(&mut self) {
+        if self.previous {
+            return;
+        }
+        if let Err(e) = self.device.disable_module(self.channel) {
+            warn!(
+                "Failed to disable module {} on guard drop: {e:?}",
+                self.channel
+            );
+        }
+    }
+}
+
+/// Restores a bias tee to its previous state when dropped, see [`BladeRF::bias_tee_guard`]
+pub struct BiasTeeGuard<'a> {
+    device: &'a BladeRF,
+    channel: Channel,
+    previous: bool,
+}
+
+impl Drop for BiasTeeGuard<'_> {
+    fn drop(&mut self) {
+        if let Err(e) = self.device.set_bias_tee(self.channel, self.previous) {
+            warn!(
+                "Failed to restore bias tee {} on guard drop: {e:?}",
+                self.channel
+            );
+        }
+    }
+}
+
+fn check_smb_frequency(rate: f64) -> Result<()> {
+    let (min, max) = (crate::SMB_FREQUENCY_MIN, crate::SMB_FREQUENCY_MAX);
+    let range = Range {
+        min: min as f64,
+        max: max as f64,
+        step: 0.0,
+    };
+    if !range.contains_value(rate) {
+        return Err(Error::msg(format!(
+            "SMB frequency {rate} Hz is outside the supported range of {min}..={max} Hz"
+        )));
+    }
+    Ok(())
+}
+
+/// Convert `gain_db` to the whole dB libbladerf takes, failing with an [`Error::Msg`] if it is
+/// not a whole number or outside `range`
+fn check_gain_db(param: &str, channel: Channel, gain_db: f64, range: &Range) -> Result<Gain> {
+    if gain_db.fract() != 0.0 {
+        return Err(Error::msg(format!(
+            "{param} {gain_db} dB on {channel} is not a whole number of dB, which libbladerf \
+             requires"
+        )));
+    }
+    if !range.contains_value(gain_db) {
+        return Err(Error::msg(format!(
+            "{param} {gain_db} dB is not valid on {channel}, valid range is {}..{} dB",
+            range.min, range.max
+        )));
+    }
+    Ok(gain_db as Gain)
+}
+
+/// Fail with an [`Error::Msg`] explaining why `value` is not valid for `param`
+fn check_in_range(
+    param: &str,
+    channel: Channel,
+    value: impl Into<u64>,
+    range: &Range,
+) -> Result<()> {
+    let value = value.into();
+    if !range.contains(value) {
+        return Err(Error::msg(format!(
+            "{param} {value} Hz is not valid on {channel}, valid range is {range}"
+        )));
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -1072,7 +2747,8 @@ mod tests {
 
         let serial = device.get_serial().unwrap();
         println!("Serial: {:?}", serial);
-        assert!(serial.len() == 32);
+        assert!(serial.len() == crate::serial_length());
+        assert!(serial.chars().all(|c| c.is_ascii_hexdigit()));
     }
 
     #[test]
@@ -1085,6 +2761,80 @@ mod tests {
         assert!(loaded);
     }
 
+    #[test]
+    fn test_vctcxo_trim() {
+        let _m = DEV_MUTEX.lock();
+
+        let device = BladeRF::open_first().unwrap();
+
+        // Calibrated boards store a trim well away from either end of the DAC range
+        let trim = device.get_vctcxo_trim().unwrap();
+        assert_ne!(trim, 0);
+        assert_ne!(trim, u16::MAX);
+    }
+
+    #[test]
+    fn test_bias_tee_round_trip() {
+        let _m = DEV_MUTEX.lock();
+
+        let device = BladeRF::open_first().unwrap();
+
+        for enable in [true, false] {
+            match device.set_bias_tee(Channel::Rx0, enable) {
+                // No bias tees on the bladeRF1
+                Err(Error::Unsupported) => return,
+                res => res.unwrap(),
+            }
+            assert_eq!(device.get_bias_tee(Channel::Rx0).unwrap(), enable);
+        }
+    }
+
+    #[test]
+    fn test_clock_output_round_trip() {
+        let _m = DEV_MUTEX.lock();
+
+        let device = BladeRF::open_first().unwrap();
+
+        match device.get_clock_select() {
+            // Clock selection is bladeRF2 only
+            Err(Error::Unsupported) => return,
+            res => assert_eq!(res.unwrap(), ClockSelect::Onboard),
+        }
+        for enable in [true, false] {
+            device.set_clock_output(enable).unwrap();
+            assert_eq!(device.get_clock_output().unwrap(), enable);
+        }
+    }
+
+    #[test]
+    fn test_rfic_product_id() {
+        let _m = DEV_MUTEX.lock();
+
+        let device = BladeRF::open_first().unwrap();
+
+        /// AD9361 product ID register, the upper 5 bits identify the part and the rest the revision
+        const REG_PRODUCT_ID: u16 = 0x037;
+        const PRODUCT_ID_9361: u8 = 0x08;
+
+        // SAFETY: the product ID register is read-only and has no read side effects
+        match unsafe { device.get_rfic_register(REG_PRODUCT_ID) } {
+            // No AD9361 on the bladeRF1
+            Err(Error::Unsupported) => {}
+            res => assert_eq!(res.unwrap() & 0xF8, PRODUCT_ID_9361),
+        }
+    }
+
+    #[test]
+    fn test_self_test_loopback() {
+        let _m = DEV_MUTEX.lock();
+
+        let device = BladeRF::open_first().unwrap();
+        let report = device.self_test_loopback().unwrap();
+        println!("Self test: {report:?}");
+        assert!(report.passed);
+        assert_eq!(device.get_loopback().unwrap(), Loopback::None);
+    }
+
     #[test]
     fn test_loopback_modes() {
         let _m = DEV_MUTEX.lock();
@@ -1122,6 +2872,145 @@ mod tests {
         assert!(i64::abs(diff) < 10);
     }
 
+    #[test]
+    fn test_try_set_out_of_range() {
+        let _m = DEV_MUTEX.lock();
+
+        let device = BladeRF::open_first().unwrap();
+
+        let range = device.get_frequency_range(Channel::Rx0).unwrap();
+        assert!(matches!(
+            device.try_set_frequency(Channel::Rx0, range.max as u64 + 1_000_000),
+            Err(Error::Msg(_))
+        ));
+        let in_range = range.snap(915e6) as u64;
+        device.try_set_frequency(Channel::Rx0, in_range).unwrap();
+
+        let range = device.get_sample_rate_range(Channel::Rx0).unwrap();
+        assert!(matches!(
+            device.try_set_sample_rate(Channel::Rx0, range.max as u32 + 1),
+            Err(Error::Msg(_))
+        ));
+    }
+
+    #[test]
+    fn test_shared_frequency() {
+        let _m = DEV_MUTEX.lock();
+
+        let device = BladeRF::open_first().unwrap();
+        let affected = device
+            .set_frequency_shared_aware(Channel::Rx0, 915_000_000u64)
+            .unwrap();
+        if !device.frequency_is_shared(Direction::RX).unwrap() {
+            assert!(affected.is_empty());
+            return;
+        }
+        assert_eq!(affected, [Channel::Rx1]);
+        assert_eq!(
+            device.get_frequency(Channel::Rx1).unwrap(),
+            device.get_frequency(Channel::Rx0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_configure_module_checked() {
+        let _m = DEV_MUTEX.lock();
+
+        let device = BladeRF::open_first().unwrap();
+        let config = ModuleConfig {
+            frequency: 915_000_000,
+            sample_rate: 2_000_000,
+            bandwidth: 1_500_000,
+            gain: 30,
+        };
+        let applied = device
+            .configure_module_checked(Channel::Rx0, config.clone())
+            .unwrap();
+        println!("Applied {config:?} as {applied:?}");
+        assert_eq!(
+            applied.sample_rate,
+            device.get_sample_rate(Channel::Rx0).unwrap()
+        );
+        assert_eq!(
+            applied.bandwidth,
+            device.get_bandwidth(Channel::Rx0).unwrap()
+        );
+
+        let out_of_range = ModuleConfig {
+            frequency: u64::MAX / 2,
+            ..config.clone()
+        };
+        assert!(matches!(
+            device.configure_module_checked(Channel::Rx0, out_of_range),
+            Err(Error::Msg(_))
+        ));
+
+        // Checked against the gain range at the new frequency
+        let out_of_range = ModuleConfig {
+            frequency: 2_400_000_000,
+            gain: 1000,
+            ..config
+        };
+        assert!(matches!(
+            device.configure_module_checked(Channel::Rx0, out_of_range),
+            Err(Error::Msg(_))
+        ));
+    }
+
+    #[test]
+    fn test_sync_reconfigure_format() {
+        let _m = DEV_MUTEX.lock();
+
+        let device = BladeRF::open_first().unwrap();
+        assert!(device
+            .sync_reconfigure_format(ChannelLayout::RxSISO, Format::Sc8Q7)
+            .is_err());
+
+        let config = StreamConfig::new(16, 8192, 8, Duration::from_secs(1)).unwrap();
+        device
+            .sync_config_with(ChannelLayout::RxSISO, Format::Sc16Q11, &config)
+            .unwrap();
+        device
+            .sync_reconfigure_format(ChannelLayout::RxSISO, Format::Sc8Q7)
+            .unwrap();
+        let _rx = device.enable_module_guard(Channel::Rx0).unwrap();
+
+        let mut buf = vec![Complex::<i8>::new(0, 0); 8192];
+        device
+            .sync_rx(&mut buf, None, Duration::from_secs(1))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_validate_channel() {
+        let _m = DEV_MUTEX.lock();
+
+        let device = BladeRF::open_first().unwrap();
+        device.validate_channel(Channel::Rx0).unwrap();
+        device.validate_channel(Channel::Tx0).unwrap();
+
+        let second = device.validate_channel(Channel::Rx1);
+        if device.board_kind().unwrap() == BoardKind::BladeRf1 {
+            assert_eq!(second, Err(Error::Inval));
+        } else {
+            assert_eq!(second, Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_require_board() {
+        let _m = DEV_MUTEX.lock();
+
+        let device = BladeRF::open_first().unwrap();
+        let board = device.board_kind().unwrap();
+        let other = match board {
+            BoardKind::BladeRf1 => BoardKind::BladeRf2,
+            BoardKind::BladeRf2 => BoardKind::BladeRf1,
+        };
+        assert_eq!(device.require_board(board), Ok(()));
+        assert_eq!(device.require_board(other), Err(Error::Unsupported));
+    }
+
     #[test]
     fn test_set_sampling() {
         let _m = DEV_MUTEX.lock();
@@ -1134,12 +3023,211 @@ mod tests {
             if e != Error::Unsupported {
                 panic!("unexpected error of value when calling set_sampling {e:?}",);
             } else {
+                assert!(!device.supports_sampling().unwrap());
+                assert_eq!(device.get_sampling(), Err(Error::Unsupported));
                 return;
             }
         };
+        assert!(device.supports_sampling().unwrap());
 
         let actual = device.get_sampling().unwrap();
 
         assert_eq!(desired, actual);
     }
+
+    #[test]
+    fn test_gain_range_cached() {
+        let _m = DEV_MUTEX.lock();
+
+        let device = BladeRF::open_first().unwrap();
+        device.set_frequency(Channel::Rx0, 915_000_000u64).unwrap();
+        let cached = device.gain_range_cached(Channel::Rx0).unwrap();
+        let queried = device.get_gain_range(Channel::Rx0).unwrap();
+        assert_eq!((cached.min, cached.max), (queried.min, queried.max));
+
+        // Retuning must not leave the range of the old frequency cached
+        for frequency in [2_400_000_000u64, 70_000_000, 915_000_000] {
+            device.set_frequency(Channel::Rx0, frequency).unwrap();
+            let cached = device.gain_range_cached(Channel::Rx0).unwrap();
+            let queried = device.get_gain_range(Channel::Rx0).unwrap();
+            assert_eq!(
+                (cached.min, cached.max),
+                (queried.min, queried.max),
+                "at {frequency} Hz"
+            );
+        }
+    }
+
+    #[test]
+    fn test_gain_db() {
+        let _m = DEV_MUTEX.lock();
+
+        let device = BladeRF::open_first().unwrap();
+        let range = device.get_gain_range(Channel::Tx0).unwrap();
+        let gain = range.min.ceil();
+        device.set_gain_db(Channel::Tx0, gain).unwrap();
+        assert_eq!(device.get_gain_db(Channel::Tx0).unwrap(), gain);
+        assert!(matches!(
+            device.set_gain_db(Channel::Tx0, gain + 0.25),
+            Err(Error::Msg(_))
+        ));
+        assert!(matches!(
+            device.set_gain_db(Channel::Tx0, range.max + 1.0),
+            Err(Error::Msg(_))
+        ));
+
+        let stage = device.get_gain_stages_typed(Channel::Tx0).unwrap()[0];
+        let range = device
+            .get_gain_stage_range_typed(Channel::Tx0, stage)
+            .unwrap();
+        let gain = range.min.ceil();
+        device.set_gain_stage_db(Channel::Tx0, stage, gain).unwrap();
+        assert_eq!(device.get_gain_stage_db(Channel::Tx0, stage).unwrap(), gain);
+        assert!(matches!(
+            device.set_gain_stage_db(Channel::Tx0, stage, gain + 0.25),
+            Err(Error::Msg(_))
+        ));
+    }
+
+    #[test]
+    fn test_gain_mode_support() {
+        let _m = DEV_MUTEX.lock();
+
+        let device = BladeRF::open_first().unwrap();
+
+        // Every board exposes manual gain control on RX
+        assert!(device
+            .channel_supports_gain_mode(Channel::Rx0, GainMode::Manual)
+            .unwrap());
+        // Second query is served from the cache
+        assert!(device
+            .channel_supports_gain_mode(Channel::Rx0, GainMode::Manual)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_agc_toggle() {
+        let _m = DEV_MUTEX.lock();
+
+        let device = BladeRF::open_first().unwrap();
+
+        assert!(!device.supports_agc(Channel::Tx0).unwrap());
+        if device.supports_agc(Channel::Rx0).unwrap() {
+            device.set_agc_enabled(Channel::Rx0, true).unwrap();
+            assert!(device.agc_enabled(Channel::Rx0).unwrap());
+        }
+        device.set_agc_enabled(Channel::Rx0, false).unwrap();
+        assert!(!device.agc_enabled(Channel::Rx0).unwrap());
+    }
+
+    #[test]
+    fn test_tx_burst_blocks_reconfigure() {
+        let _m = DEV_MUTEX.lock();
+
+        let device = BladeRF::open_first().unwrap();
+        let config = StreamConfig::new(16, 8192, 8, Duration::from_secs(1)).unwrap();
+        device
+            .sync_config_with(ChannelLayout::TxSISO, Format::Sc16Q11Meta, &config)
+            .unwrap();
+        device.enable_module(Channel::Tx0).unwrap();
+
+        let buf = vec![Complex::<i16>::new(0, 0); 8192];
+        let mut meta = Metadata::new().burst_start().tx_now();
+        device
+            .sync_tx(&buf, Some(&mut meta), Duration::from_secs(1))
+            .unwrap();
+        assert!(matches!(
+            device.sync_config_with(ChannelLayout::TxSISO, Format::Sc16Q11Meta, &config),
+            Err(Error::Msg(_))
+        ));
+
+        device.disable_module(Channel::Tx0).unwrap();
+        device
+            .sync_config_with(ChannelLayout::TxSISO, Format::Sc16Q11Meta, &config)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_module_guard_restores_state() {
+        let _m = DEV_MUTEX.lock();
+
+        let device = BladeRF::open_first().unwrap();
+
+        drop(device.enable_module_guard(Channel::Rx0).unwrap());
+        assert!(!device.is_module_enabled(Channel::Rx0));
+
+        device.enable_module(Channel::Rx0).unwrap();
+        drop(device.enable_module_guard(Channel::Rx0).unwrap());
+        assert!(device.is_module_enabled(Channel::Rx0));
+        device.disable_module(Channel::Rx0).unwrap();
+    }
+
+    #[test]
+    fn test_enable_module_twice() {
+        let _m = DEV_MUTEX.lock();
+
+        let device = BladeRF::open_first().unwrap();
+
+        device.enable_module(Channel::Rx0).unwrap();
+        device.enable_module(Channel::Rx0).unwrap();
+        assert!(device.is_module_enabled(Channel::Rx0));
+
+        device.disable_module(Channel::Rx0).unwrap();
+        device.disable_module(Channel::Rx0).unwrap();
+        assert!(!device.is_module_enabled(Channel::Rx0));
+    }
+
+    #[test]
+    fn test_simultaneous_rx_tx() {
+        let _m = DEV_MUTEX.lock();
+
+        let device = BladeRF::open_first().unwrap();
+        let config = StreamConfig::new(16, 8192, 8, Duration::from_secs(1)).unwrap();
+        device
+            .sync_config_with(ChannelLayout::RxSISO, Format::Sc16Q11, &config)
+            .unwrap();
+        device
+            .sync_config_with(ChannelLayout::TxSISO, Format::Sc16Q11, &config)
+            .unwrap();
+        let _rx = device.enable_module_guard(Channel::Rx0).unwrap();
+        let _tx = device.enable_module_guard(Channel::Tx0).unwrap();
+
+        thread::scope(|s| {
+            let rx = s.spawn(|| {
+                let mut buf = vec![Complex::<i16>::new(0, 0); 8192];
+                for _ in 0..32 {
+                    device.sync_rx(&mut buf, None, Duration::from_secs(1))?;
+                }
+                Ok::<_, Error>(())
+            });
+            let tx = s.spawn(|| {
+                let buf = vec![Complex::<i16>::new(0, 0); 8192];
+                for _ in 0..32 {
+                    device.sync_tx(&buf, None, Duration::from_secs(1))?;
+                }
+                Ok::<_, Error>(())
+            });
+            rx.join().unwrap().unwrap();
+            tx.join().unwrap().unwrap();
+        });
+    }
+
+    #[test]
+    fn test_rx_counter_mux() {
+        let _m = DEV_MUTEX.lock();
+
+        let device = BladeRF::open_first().unwrap();
+        let config = StreamConfig::new(16, 8192, 8, Duration::from_secs(1)).unwrap();
+        device
+            .sync_config_with(ChannelLayout::RxSISO, Format::Sc16Q11, &config)
+            .unwrap();
+        device.set_rx_mux(RxMux::Counter32bit).unwrap();
+        let _rx = device.enable_module_guard(Channel::Rx0).unwrap();
+
+        let mut buf = vec![Complex::<i16>::new(0, 0); 8192];
+        let res = device.sync_rx(&mut buf, None, Duration::from_secs(1));
+        device.set_rx_mux(RxMux::Baseband).unwrap();
+        res.unwrap();
+        crate::verify_counter(&buf).unwrap();
+    }
 }
@@ -1,3 +1,5 @@
+use std::ffi::{c_int, CStr};
+
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -44,19 +46,42 @@ pub enum Error {
     WouldBlock,
     #[error("Insufficient initialization for the requested operation")]
     NotInit,
-    #[error("libbladerf code {0}")]
+    /// A libbladerf error code without a dedicated variant
+    #[error("libbladerf code {0}: {}", strerror(*.0))]
     BladeRfCode(isize),
     /// An arbitrary string error
     #[error("{0}")]
     Msg(Box<str>),
 }
 
+/// Look up libbladerf's own description of an error code
+fn strerror(code: isize) -> String {
+    let ptr = unsafe { crate::sys::bladerf_strerror(code as c_int) };
+    if ptr.is_null() {
+        return String::from("Unknown error");
+    }
+    // SAFETY: bladerf_strerror returns a pointer to a static, nul terminated string
+    unsafe { CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .into_owned()
+}
+
 impl From<String> for Error {
     fn from(value: String) -> Self {
         Error::Msg(value.into_boxed_str())
     }
 }
 
+impl From<std::io::Error> for Error {
+    /// A missing file maps to [`Error::NoFile`], anything else keeps the I/O error's message.
+    fn from(value: std::io::Error) -> Self {
+        match value.kind() {
+            std::io::ErrorKind::NotFound => Error::NoFile,
+            _ => Error::msg(value.to_string()),
+        }
+    }
+}
+
 impl Error {
     pub fn msg(msg: impl Into<String>) -> Self {
         Error::Msg(msg.into().into())
@@ -90,3 +115,17 @@ impl Error {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn io_error_conversion() {
+        let not_found = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+        assert_eq!(Error::from(not_found), Error::NoFile);
+
+        let other = std::io::Error::other("disk on fire");
+        assert_eq!(Error::from(other), Error::msg("disk on fire"));
+    }
+}
@@ -0,0 +1,16 @@
+//! Controllers for the bladeRF expansion boards
+//!
+//! Each controller borrows the [`crate::BladeRF`] it was attached to and drives the board through
+//! the expansion GPIO and configuration calls on it.
+
+mod xb100;
+pub use xb100::*;
+mod xb200;
+pub use xb200::*;
+mod xb300;
+pub use xb300::*;
+
+/// Mask of expansion GPIO pin `n`, numbered from 1 as in the board schematics
+pub const fn xb_gpio(n: u32) -> u32 {
+    1 << (n - 1)
+}
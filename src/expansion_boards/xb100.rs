@@ -0,0 +1,242 @@
+use crate::{BladeRF, Error, ExpansionModule, Result};
+
+use super::xb_gpio;
+
+/// The eight single-color LEDs on the XB-100
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Xb100Led {
+    D1,
+    D2,
+    D3,
+    D4,
+    D5,
+    D6,
+    D7,
+    D8,
+}
+
+impl Xb100Led {
+    pub fn mask(self) -> u32 {
+        xb_gpio(match self {
+            Xb100Led::D1 => 24,
+            Xb100Led::D2 => 32,
+            Xb100Led::D3 => 30,
+            Xb100Led::D4 => 28,
+            Xb100Led::D5 => 23,
+            Xb100Led::D6 => 25,
+            Xb100Led::D7 => 31,
+            Xb100Led::D8 => 29,
+        })
+    }
+}
+
+/// The color channels of the XB-100 tricolor LED
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Xb100TriColor {
+    Red,
+    Green,
+    Blue,
+}
+
+impl Xb100TriColor {
+    pub fn mask(self) -> u32 {
+        xb_gpio(match self {
+            Xb100TriColor::Red => 27,
+            Xb100TriColor::Green => 26,
+            Xb100TriColor::Blue => 1,
+        })
+    }
+}
+
+/// The four positions of the XB-100 DIP switch
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Xb100DipSwitch {
+    Sw1,
+    Sw2,
+    Sw3,
+    Sw4,
+}
+
+impl Xb100DipSwitch {
+    pub fn mask(self) -> u32 {
+        xb_gpio(match self {
+            Xb100DipSwitch::Sw1 => 3,
+            Xb100DipSwitch::Sw2 => 2,
+            Xb100DipSwitch::Sw3 => 5,
+            Xb100DipSwitch::Sw4 => 4,
+        })
+    }
+}
+
+/// The XB-100 push buttons, named after their reference designators
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Xb100Button {
+    J6,
+    J7,
+    J8,
+}
+
+impl Xb100Button {
+    pub fn mask(self) -> u32 {
+        xb_gpio(match self {
+            Xb100Button::J6 => 19,
+            Xb100Button::J7 => 20,
+            Xb100Button::J8 => 21,
+        })
+    }
+}
+
+/// The general purpose header pins of the XB-100, named `J<header>_<pin>`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Xb100Pin {
+    J2_3,
+    J2_4,
+    J3_3,
+    J3_4,
+    J4_3,
+    J4_4,
+    J5_3,
+    J5_4,
+    J11_2,
+    J11_3,
+    J11_4,
+    J11_5,
+    J12_2,
+    J12_3,
+    J12_4,
+    J12_5,
+}
+
+impl Xb100Pin {
+    pub fn mask(self) -> u32 {
+        xb_gpio(match self {
+            Xb100Pin::J2_3 => 7,
+            Xb100Pin::J2_4 => 8,
+            Xb100Pin::J3_3 => 9,
+            Xb100Pin::J3_4 => 10,
+            Xb100Pin::J4_3 => 11,
+            Xb100Pin::J4_4 => 12,
+            Xb100Pin::J5_3 => 13,
+            Xb100Pin::J5_4 => 14,
+            Xb100Pin::J11_2 => 15,
+            Xb100Pin::J11_3 => 16,
+            Xb100Pin::J11_4 => 17,
+            Xb100Pin::J11_5 => 18,
+            Xb100Pin::J12_2 => 19,
+            Xb100Pin::J12_3 => 20,
+            Xb100Pin::J12_4 => 21,
+            Xb100Pin::J12_5 => 22,
+        })
+    }
+}
+
+/// Controller for the XB-100 GPIO expansion board (bladeRF1 only)
+///
+/// Some header pins share GPIO lines with the buttons, so configuring one of those pins as an
+/// output also affects the matching button.
+pub struct Xb100<'a> {
+    device: &'a BladeRF,
+}
+
+impl<'a> Xb100<'a> {
+    /// Attach the XB-100 to `device`, configuring the LEDs as outputs and the switches and
+    /// buttons as inputs.
+    ///
+    /// Fails with [`Error::Inval`] if a different expansion board is already attached.
+    pub fn attach(device: &'a BladeRF) -> Result<Self> {
+        match device.get_attached_expansion()? {
+            ExpansionModule::Xb100 => {}
+            ExpansionModule::None => device.expansion_attach(ExpansionModule::Xb100)?,
+            other => {
+                log::warn!("Cannot attach XB-100, {other:?} is already attached");
+                return Err(Error::Inval);
+            }
+        }
+
+        let leds = [
+            Xb100Led::D1,
+            Xb100Led::D2,
+            Xb100Led::D3,
+            Xb100Led::D4,
+            Xb100Led::D5,
+            Xb100Led::D6,
+            Xb100Led::D7,
+            Xb100Led::D8,
+        ]
+        .into_iter()
+        .map(Xb100Led::mask)
+        .chain(
+            [
+                Xb100TriColor::Red,
+                Xb100TriColor::Green,
+                Xb100TriColor::Blue,
+            ]
+            .into_iter()
+            .map(Xb100TriColor::mask),
+        )
+        .fold(0, |acc, mask| acc | mask);
+        let inputs = [
+            Xb100DipSwitch::Sw1,
+            Xb100DipSwitch::Sw2,
+            Xb100DipSwitch::Sw3,
+            Xb100DipSwitch::Sw4,
+        ]
+        .into_iter()
+        .map(Xb100DipSwitch::mask)
+        .chain(
+            [Xb100Button::J6, Xb100Button::J7, Xb100Button::J8]
+                .into_iter()
+                .map(Xb100Button::mask),
+        )
+        .fold(0, |acc, mask| acc | mask);
+        device.expansion_gpio_dir_masked_write(leds | inputs, leds)?;
+
+        Ok(Self { device })
+    }
+
+    /// Drive the pin of `led` high (`true`) or low (`false`)
+    pub fn set_led(&self, led: Xb100Led, level: bool) -> Result<()> {
+        self.write_mask(led.mask(), level)
+    }
+
+    /// Drive one color of the tricolor LED high (`true`) or low (`false`)
+    pub fn set_tricolor(&self, color: Xb100TriColor, level: bool) -> Result<()> {
+        self.write_mask(color.mask(), level)
+    }
+
+    /// Read the level of a DIP switch position
+    pub fn dip_switch(&self, switch: Xb100DipSwitch) -> Result<bool> {
+        self.read_mask(switch.mask())
+    }
+
+    /// Read the level of a push button
+    pub fn button(&self, button: Xb100Button) -> Result<bool> {
+        self.read_mask(button.mask())
+    }
+
+    /// Configure a header pin as an output (`true`) or input (`false`)
+    pub fn set_pin_output(&self, pin: Xb100Pin, output: bool) -> Result<()> {
+        let mask = pin.mask();
+        self.device
+            .expansion_gpio_dir_masked_write(mask, if output { mask } else { 0 })
+    }
+
+    /// Drive a header pin previously configured as an output
+    pub fn write_pin(&self, pin: Xb100Pin, level: bool) -> Result<()> {
+        self.write_mask(pin.mask(), level)
+    }
+
+    /// Read the level of a header pin
+    pub fn read_pin(&self, pin: Xb100Pin) -> Result<bool> {
+        self.read_mask(pin.mask())
+    }
+
+    fn write_mask(&self, mask: u32, level: bool) -> Result<()> {
+        self.device
+            .expansion_gpio_masked_write(mask, if level { mask } else { 0 })
+    }
+
+    fn read_mask(&self, mask: u32) -> Result<bool> {
+        Ok(self.device.expansion_gpio_read()? & mask != 0)
+    }
+}
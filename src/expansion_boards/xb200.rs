@@ -0,0 +1,211 @@
+use strum::FromRepr;
+
+use crate::{sys::*, BladeRF, Channel, Direction, Error, ExpansionModule, Result};
+
+/// Highest frequency, in Hz, the XB-200 mixer path covers. Above it the bladeRF1 tunes directly
+/// and the board should be bypassed.
+pub const XB200_FREQUENCY_MAX: u64 = 300_000_000;
+
+/// Filter banks of the XB-200
+#[derive(Copy, Clone, Debug, FromRepr, PartialEq, Eq)]
+#[repr(u32)]
+pub enum Xb200Filter {
+    /// 50-54 MHz (6 meter band)
+    Filter50M = bladerf_xb200_filter_BLADERF_XB200_50M,
+    /// 144-148 MHz (2 meter band)
+    Filter144M = bladerf_xb200_filter_BLADERF_XB200_144M,
+    /// 222-225 MHz (1.25 meter band)
+    Filter222M = bladerf_xb200_filter_BLADERF_XB200_222M,
+    /// Custom filter, populated by the user on the board
+    Custom = bladerf_xb200_filter_BLADERF_XB200_CUSTOM,
+    /// Let libbladerf select the filter covering the frequency within its 1 dB points
+    Auto1Db = bladerf_xb200_filter_BLADERF_XB200_AUTO_1DB,
+    /// Let libbladerf select the filter covering the frequency within its 3 dB points
+    Auto3Db = bladerf_xb200_filter_BLADERF_XB200_AUTO_3DB,
+}
+
+impl Xb200Filter {
+    /// Filter bank whose 1 dB passband covers `frequency_hz`, falling back to
+    /// [`Xb200Filter::Custom`] between the fixed bands, like libbladerf's
+    /// [`Xb200Filter::Auto1Db`].
+    ///
+    /// Fails with [`Error::Range`] above [`XB200_FREQUENCY_MAX`].
+    pub fn for_frequency(frequency_hz: u64) -> Result<Self> {
+        match frequency_hz {
+            37_774_405..=59_535_436 => Ok(Xb200Filter::Filter50M),
+            128_326_173..=166_711_171 => Ok(Xb200Filter::Filter144M),
+            187_593_160..=245_346_403 => Ok(Xb200Filter::Filter222M),
+            0..=XB200_FREQUENCY_MAX => Ok(Xb200Filter::Custom),
+            _ => {
+                log::warn!(
+                    "{frequency_hz} Hz is above the XB-200 range of {XB200_FREQUENCY_MAX} Hz"
+                );
+                Err(Error::Range)
+            }
+        }
+    }
+}
+
+impl TryFrom<bladerf_xb200_filter> for Xb200Filter {
+    type Error = Error;
+
+    fn try_from(value: bladerf_xb200_filter) -> Result<Self> {
+        Self::from_repr(value)
+            .ok_or_else(|| Error::msg(format!("Invalid Xb200Filter value: {value}")))
+    }
+}
+
+/// Signal path through the XB-200
+#[derive(Copy, Clone, Debug, FromRepr, PartialEq, Eq)]
+#[repr(u32)]
+pub enum Xb200Path {
+    /// Bypass the board, the bladeRF1 tunes directly
+    Bypass = bladerf_xb200_path_BLADERF_XB200_BYPASS,
+    /// Route through the filter banks and the mixer, for frequencies below
+    /// [`XB200_FREQUENCY_MAX`]
+    Mix = bladerf_xb200_path_BLADERF_XB200_MIX,
+}
+
+impl TryFrom<bladerf_xb200_path> for Xb200Path {
+    type Error = Error;
+
+    fn try_from(value: bladerf_xb200_path) -> Result<Self> {
+        Self::from_repr(value)
+            .ok_or_else(|| Error::msg(format!("Invalid Xb200Path value: {value}")))
+    }
+}
+
+/// Controller for the XB-200 transverter board (bladeRF1 only)
+///
+/// Filter bank and path are set per direction. Only [`Channel::Rx0`] and [`Channel::Tx0`] exist
+/// on the bladeRF1.
+pub struct Xb200<'a> {
+    device: &'a BladeRF,
+}
+
+impl<'a> Xb200<'a> {
+    /// Attach the XB-200 to `device`.
+    ///
+    /// Fails with [`Error::Inval`] if a different expansion board is already attached.
+    pub fn attach(device: &'a BladeRF) -> Result<Self> {
+        match device.get_attached_expansion()? {
+            ExpansionModule::Xb200 => {}
+            ExpansionModule::None => device.expansion_attach(ExpansionModule::Xb200)?,
+            other => {
+                log::warn!("Cannot attach XB-200, {other:?} is already attached");
+                return Err(Error::Inval);
+            }
+        }
+
+        Ok(Self { device })
+    }
+
+    /// Select the filter bank used by `channel`
+    pub fn set_filterbank(&self, channel: Channel, filter: Xb200Filter) -> Result<()> {
+        let res = unsafe {
+            bladerf_xb200_set_filterbank(
+                self.device.device_ptr(),
+                channel as bladerf_channel,
+                filter as bladerf_xb200_filter,
+            )
+        };
+        check_res!(res);
+        Ok(())
+    }
+
+    /// Filter bank used by `channel`
+    pub fn get_filterbank(&self, channel: Channel) -> Result<Xb200Filter> {
+        let mut filter = bladerf_xb200_filter_BLADERF_XB200_CUSTOM;
+        let res = unsafe {
+            bladerf_xb200_get_filterbank(
+                self.device.device_ptr(),
+                channel as bladerf_channel,
+                &mut filter,
+            )
+        };
+        check_res!(res);
+        Xb200Filter::try_from(filter)
+    }
+
+    /// Route `channel` through or around the board
+    pub fn set_path(&self, channel: Channel, path: Xb200Path) -> Result<()> {
+        let res = unsafe {
+            bladerf_xb200_set_path(
+                self.device.device_ptr(),
+                channel as bladerf_channel,
+                path as bladerf_xb200_path,
+            )
+        };
+        check_res!(res);
+        Ok(())
+    }
+
+    /// Path of `channel` through the board
+    pub fn get_path(&self, channel: Channel) -> Result<Xb200Path> {
+        let mut path = bladerf_xb200_path_BLADERF_XB200_BYPASS;
+        let res = unsafe {
+            bladerf_xb200_get_path(
+                self.device.device_ptr(),
+                channel as bladerf_channel,
+                &mut path,
+            )
+        };
+        check_res!(res);
+        Xb200Path::try_from(path)
+    }
+
+    /// Route `direction` through the mixer with the filter bank covering `frequency_hz`, see
+    /// [`Xb200Filter::for_frequency`].
+    ///
+    /// Only the board is configured, tune the channel with [`BladeRF::set_frequency`] as usual.
+    /// Fails with [`Error::Range`] above [`XB200_FREQUENCY_MAX`], where the board should be
+    /// bypassed instead.
+    pub fn auto_configure(&self, direction: Direction, frequency_hz: u64) -> Result<Xb200Filter> {
+        let filter = Xb200Filter::for_frequency(frequency_hz)?;
+        let channel = match direction {
+            Direction::RX => Channel::Rx0,
+            Direction::TX => Channel::Tx0,
+        };
+
+        self.set_filterbank(channel, filter)?;
+        self.set_path(channel, Xb200Path::Mix)?;
+        Ok(filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xb200_filter_selection() {
+        assert_eq!(
+            Xb200Filter::for_frequency(52_000_000),
+            Ok(Xb200Filter::Filter50M)
+        );
+        assert_eq!(
+            Xb200Filter::for_frequency(146_000_000),
+            Ok(Xb200Filter::Filter144M)
+        );
+        assert_eq!(
+            Xb200Filter::for_frequency(223_500_000),
+            Ok(Xb200Filter::Filter222M)
+        );
+        assert_eq!(
+            Xb200Filter::for_frequency(100_000_000),
+            Ok(Xb200Filter::Custom)
+        );
+        assert_eq!(
+            Xb200Filter::for_frequency(1_000_000),
+            Ok(Xb200Filter::Custom)
+        );
+        assert_eq!(
+            Xb200Filter::for_frequency(XB200_FREQUENCY_MAX),
+            Ok(Xb200Filter::Custom)
+        );
+        assert_eq!(
+            Xb200Filter::for_frequency(XB200_FREQUENCY_MAX + 1),
+            Err(Error::Range)
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use strum::FromRepr;
+
+use crate::{sys::*, BladeRF, Error, ExpansionModule, Result};
+
+/// Direction of the XB-300 TRX port
+#[derive(Copy, Clone, Debug, FromRepr, PartialEq, Eq)]
+#[repr(i32)]
+pub enum Xb300Trx {
+    /// Route the TRX port through the PA to transmit
+    Tx = bladerf_xb300_trx_BLADERF_XB300_TRX_TX,
+    /// Route the TRX port through the LNA to receive
+    Rx = bladerf_xb300_trx_BLADERF_XB300_TRX_RX,
+}
+
+impl TryFrom<bladerf_xb300_trx> for Xb300Trx {
+    type Error = Error;
+
+    fn try_from(value: bladerf_xb300_trx) -> Result<Self> {
+        Self::from_repr(value).ok_or_else(|| Error::msg(format!("Invalid Xb300Trx value: {value}")))
+    }
+}
+
+/// Amplifiers on the XB-300
+#[derive(Copy, Clone, Debug, FromRepr, PartialEq, Eq)]
+#[repr(i32)]
+pub enum Xb300Amplifier {
+    /// TX power amplifier
+    Pa = bladerf_xb300_amplifier_BLADERF_XB300_AMP_PA,
+    /// RX low noise amplifier
+    Lna = bladerf_xb300_amplifier_BLADERF_XB300_AMP_LNA,
+    /// Auxiliary TX power amplifier
+    PaAux = bladerf_xb300_amplifier_BLADERF_XB300_AMP_PA_AUX,
+}
+
+impl TryFrom<bladerf_xb300_amplifier> for Xb300Amplifier {
+    type Error = Error;
+
+    fn try_from(value: bladerf_xb300_amplifier) -> Result<Self> {
+        Self::from_repr(value)
+            .ok_or_else(|| Error::msg(format!("Invalid Xb300Amplifier value: {value}")))
+    }
+}
+
+/// Controller for the XB-300 amplifier board (bladeRF1 only)
+///
+/// The TRX port is shared between transmit and receive, [`Xb300::set_trx`] selects which path
+/// it is connected to. The amplifiers are switched independently of the TRX direction, so
+/// enable the one matching the selected path.
+pub struct Xb300<'a> {
+    device: &'a BladeRF,
+}
+
+impl<'a> Xb300<'a> {
+    /// Attach the XB-300 to `device`.
+    ///
+    /// Fails with [`Error::Inval`] if a different expansion board is already attached.
+    pub fn attach(device: &'a BladeRF) -> Result<Self> {
+        match device.get_attached_expansion()? {
+            ExpansionModule::Xb300 => {}
+            ExpansionModule::None => device.expansion_attach(ExpansionModule::Xb300)?,
+            other => {
+                log::warn!("Cannot attach XB-300, {other:?} is already attached");
+                return Err(Error::Inval);
+            }
+        }
+
+        Ok(Self { device })
+    }
+
+    /// Connect the TRX port to the transmit or receive path
+    pub fn set_trx(&self, trx: Xb300Trx) -> Result<()> {
+        let res =
+            unsafe { bladerf_xb300_set_trx(self.device.device_ptr(), trx as bladerf_xb300_trx) };
+        check_res!(res);
+        Ok(())
+    }
+
+    /// Path the TRX port is connected to
+    pub fn get_trx(&self) -> Result<Xb300Trx> {
+        let mut trx = bladerf_xb300_trx_BLADERF_XB300_TRX_INVAL;
+        let res = unsafe { bladerf_xb300_get_trx(self.device.device_ptr(), &mut trx) };
+        check_res!(res);
+        Xb300Trx::try_from(trx)
+    }
+
+    /// Power `amplifier` on (`true`) or off (`false`)
+    pub fn set_amplifier_enabled(&self, amplifier: Xb300Amplifier, enabled: bool) -> Result<()> {
+        let res = unsafe {
+            bladerf_xb300_set_amplifier_enable(
+                self.device.device_ptr(),
+                amplifier as bladerf_xb300_amplifier,
+                enabled,
+            )
+        };
+        check_res!(res);
+        Ok(())
+    }
+
+    /// Whether `amplifier` is powered on
+    pub fn amplifier_enabled(&self, amplifier: Xb300Amplifier) -> Result<bool> {
+        let mut enabled = false;
+        let res = unsafe {
+            bladerf_xb300_get_amplifier_enable(
+                self.device.device_ptr(),
+                amplifier as bladerf_xb300_amplifier,
+                &mut enabled,
+            )
+        };
+        check_res!(res);
+        Ok(enabled)
+    }
+
+    /// Output power of the PA in dBm, as measured by its power detector
+    pub fn output_power(&self) -> Result<f32> {
+        let mut power = 0.0;
+        let res = unsafe { bladerf_xb300_get_output_power(self.device.device_ptr(), &mut power) };
+        check_res!(res);
+        Ok(power)
+    }
+}
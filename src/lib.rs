@@ -12,6 +12,11 @@ pub use types::*;
 #[macro_use]
 mod bladerf;
 pub use bladerf::*;
+pub mod expansion_boards;
+mod stream;
+pub use stream::*;
+#[cfg(feature = "tokio")]
+mod sync_async;
 
 pub use libbladerf_sys as sys;
 use sys::*;
@@ -30,10 +35,86 @@ pub fn version() -> Result<Version> {
     Ok(unsafe { Version::from_ffi(&version) })
 }
 
+/// Number of characters in a device serial number, see [`BladeRF::get_serial`]
+pub fn serial_length() -> usize {
+    // The sys constant includes the nul terminator
+    BLADERF_SERIAL_LENGTH as usize - 1
+}
+
+/// Lowest frequency, in Hz, the bladeRF1 SMB clock port can output or accept
+pub const SMB_FREQUENCY_MIN: u32 = BLADERF_SMB_FREQUENCY_MIN;
+/// Highest frequency, in Hz, the bladeRF1 SMB clock port can output or accept
+pub const SMB_FREQUENCY_MAX: u32 = BLADERF_SMB_FREQUENCY_MAX;
+
+/// Oldest libbladerf release [`BladeRF::check_compatibility`] accepts.
+///
+/// libbladerf 2.0.0 introduced the channel based API (`bladerf_channel`, `BLADERF_CHANNEL_RX`,
+/// `bladerf_get_board_name`, ...) that this crate is written against, and is the first release
+/// supporting the bladeRF2. A few functions wrapped here were added in later releases, and the
+/// crate does not link against releases that lack them.
+pub const MIN_LIBBLADERF_VERSION: Version = Version::new(2, 0, 0);
+/// Oldest FX3 firmware [`BladeRF::check_compatibility`] accepts.
+///
+/// This is a floor set by this crate rather than a requirement published by Nuand, and older
+/// firmware may still work. libbladerf applies its own compatibility checks when the device is
+/// opened regardless.
+pub const MIN_FIRMWARE_VERSION: Version = Version::new(2, 4, 0);
+/// Oldest FPGA image [`BladeRF::check_compatibility`] accepts.
+///
+/// This is a floor set by this crate rather than a requirement published by Nuand, and older
+/// images may still work. libbladerf applies its own compatibility checks when the FPGA is
+/// loaded regardless.
+pub const MIN_FPGA_VERSION: Version = Version::new(0, 15, 3);
+
+/// Size in bytes of a SPI flash page, the unit of [`BladeRF::read_flash`] and
+/// [`BladeRF::write_flash`]
+pub const FLASH_PAGE_SIZE: u32 = BLADERF_FLASH_PAGE_SIZE;
+/// Size in bytes of a SPI flash erase block, the unit of [`BladeRF::erase_flash`]
+pub const FLASH_ERASE_BLOCK_SIZE: u32 = BLADERF_FLASH_EB_SIZE;
+
+/// Fields of the bladeRF1 FPGA configuration GPIO register, see [`BladeRF::config_gpio_read`]
+pub mod config_gpio {
+    use crate::sys;
+
+    /// LMS6002D RX enable
+    pub const LMS_RX_ENABLE: u32 = sys::BLADERF_GPIO_LMS_RX_ENABLE;
+    /// LMS6002D TX enable
+    pub const LMS_TX_ENABLE: u32 = sys::BLADERF_GPIO_LMS_TX_ENABLE;
+    /// TX high band switch
+    pub const TX_HB_ENABLE: u32 = sys::BLADERF_GPIO_TX_HB_ENABLE;
+    /// TX low band switch
+    pub const TX_LB_ENABLE: u32 = sys::BLADERF_GPIO_TX_LB_ENABLE;
+    /// RX high band switch
+    pub const RX_HB_ENABLE: u32 = sys::BLADERF_GPIO_RX_HB_ENABLE;
+    /// RX low band switch
+    pub const RX_LB_ENABLE: u32 = sys::BLADERF_GPIO_RX_LB_ENABLE;
+    /// Use smaller DMA transfers, as used on USB 2.0 links
+    pub const FEATURE_SMALL_DMA_XFER: u32 = sys::BLADERF_GPIO_FEATURE_SMALL_DMA_XFER;
+    /// Replace RX samples with a counter
+    pub const COUNTER_ENABLE: u32 = sys::BLADERF_GPIO_COUNTER_ENABLE;
+    /// Enable the sample timestamp counter
+    pub const TIMESTAMP: u32 = sys::BLADERF_GPIO_TIMESTAMP;
+    /// Run the timestamp counter at half rate
+    pub const TIMESTAMP_DIV2: u32 = sys::BLADERF_GPIO_TIMESTAMP_DIV2;
+    /// Enable the FPGA automatic gain control
+    pub const AGC_ENABLE: u32 = sys::BLADERF_GPIO_AGC_ENABLE;
+    /// Packet mode, used by the packet metadata format
+    pub const PACKET: u32 = sys::BLADERF_GPIO_PACKET;
+}
+
 pub fn set_log_level(level: LogLevel) {
     unsafe { bladerf_log_set_verbosity(level as u32) }
 }
 
+/// Match libbladerf's verbosity to the `log` crate's current [`log::max_level`].
+///
+/// libbladerf has no hook for a log handler and always prints its messages to stderr, so they
+/// cannot be routed through the `log` crate. This at least keeps libbladerf's output in step
+/// with the application's configured level. Call it after initializing the logger.
+pub fn set_log_level_from_logger() {
+    set_log_level(log::max_level().into());
+}
+
 pub fn set_usb_reset_on_open(enabled: bool) {
     unsafe { bladerf_set_usb_reset_on_open(enabled) };
 }
@@ -54,3 +135,23 @@ pub fn get_device_list() -> Result<Vec<DevInfo>> {
 
     Ok(devs)
 }
+
+/// List attached BladeRF devices using `backend`, or every device for [`Backend::Any`].
+///
+/// Unlike [`get_device_list`], this returns an empty list rather than [`Error::Nodev`] when no
+/// device matches.
+pub fn get_device_list_by_backend(backend: Backend) -> Result<Vec<DevInfo>> {
+    let devices = match get_device_list() {
+        Ok(devices) => devices,
+        Err(Error::Nodev) => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    if backend == Backend::Any {
+        return Ok(devices);
+    }
+
+    Ok(devices
+        .into_iter()
+        .filter(|dev| dev.backend().ok() == Some(backend))
+        .collect())
+}
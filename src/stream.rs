@@ -0,0 +1,795 @@
+//! Asynchronous streaming on top of libbladerf's callback based stream API
+//!
+//! libbladerf drives an async stream from `bladerf_stream`, which blocks until the stream ends and
+//! calls back into the application as each buffer completes. The types here run that call on a
+//! dedicated thread and exchange sample buffers with the application over bounded channels.
+//! [`BorrowedRxStream`] lends libbladerf's own buffers out instead of copying them.
+
+use std::{
+    ffi::c_void,
+    marker::PhantomData,
+    ops::Deref,
+    ptr, slice,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError, Sender, SyncSender, TrySendError},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use log::warn;
+
+use crate::{sys::*, BladeRF, ChannelLayout, Error, Format, Result, SampleFormat, StreamConfig};
+
+/// Buffers allocated by `bladerf_init_stream`, handed back to libbladerf round-robin.
+///
+/// With `num_transfers < num_buffers`, the buffer handed out is never one still in flight.
+struct BufferRing {
+    buffers: *mut *mut c_void,
+    len: usize,
+    next: usize,
+}
+
+impl BufferRing {
+    fn next(&mut self) -> *mut c_void {
+        // SAFETY: buffers points to `len` buffer pointers owned by the stream
+        let buffer = unsafe { *self.buffers.add(self.next) };
+        self.next = (self.next + 1) % self.len;
+        buffer
+    }
+}
+
+/// State behind the callback's `user_data` pointer
+trait StreamState: Send + 'static {
+    fn ring(&mut self) -> &mut BufferRing;
+
+    /// Handle a completed buffer (null for the initial TX requests), returning the next buffer
+    /// for libbladerf, or null to shut the stream down.
+    fn on_buffer(&mut self, samples: *mut c_void, num_samples: usize) -> *mut c_void;
+}
+
+unsafe extern "C" fn stream_callback<S: StreamState>(
+    _dev: *mut bladerf,
+    _stream: *mut bladerf_stream,
+    _meta: *mut bladerf_metadata,
+    samples: *mut c_void,
+    num_samples: usize,
+    user_data: *mut c_void,
+) -> *mut c_void {
+    // SAFETY: user_data is the boxed state, only accessed from callbacks while the stream runs
+    let state = unsafe { &mut *user_data.cast::<S>() };
+    state.on_buffer(samples, num_samples)
+}
+
+/// Raw pointers moved to the streaming thread, freeing the stream and its state when dropped.
+///
+/// The thread hands them back once `bladerf_stream` returns, so that the stream buffers stay
+/// allocated until the owning [`StreamRunner`] is stopped, however the stream ended.
+struct StreamPtrs {
+    stream: *mut bladerf_stream,
+    state: *mut c_void,
+    free_state: unsafe fn(*mut c_void),
+}
+
+// SAFETY: the stream and its state are only used by the thread currently holding the pointers
+unsafe impl Send for StreamPtrs {}
+
+impl StreamPtrs {
+    fn new<S: StreamState>(stream: *mut bladerf_stream, state: *mut S) -> Self {
+        unsafe fn free_state<S>(state: *mut c_void) {
+            // SAFETY: state came from Box::into_raw of an S
+            drop(unsafe { Box::from_raw(state.cast::<S>()) });
+        }
+
+        Self {
+            stream,
+            state: state.cast(),
+            free_state: free_state::<S>,
+        }
+    }
+
+    /// Run the stream to completion, handing the pointers back to be freed
+    fn run(self, layout: ChannelLayout) -> (Self, Result<()>) {
+        let res = unsafe { bladerf_stream(self.stream, layout as bladerf_channel_layout) };
+        let res = if res < 0 {
+            Err(Error::from_bladerf_code(res as isize))
+        } else {
+            Ok(())
+        };
+        (self, res)
+    }
+}
+
+impl Drop for StreamPtrs {
+    fn drop(&mut self) {
+        unsafe { bladerf_deinit_stream(self.stream) };
+        // SAFETY: the stream that used the state is gone
+        unsafe { (self.free_state)(self.state) };
+    }
+}
+
+/// A running stream: the thread calling `bladerf_stream` and the flag asking it to stop
+struct StreamRunner {
+    device: Arc<BladeRF>,
+    layout: ChannelLayout,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<(StreamPtrs, Result<()>)>>,
+}
+
+impl StreamRunner {
+    fn start<S: StreamState>(
+        device: Arc<BladeRF>,
+        layout: ChannelLayout,
+        format: Format,
+        config: &StreamConfig,
+        stop: Arc<AtomicBool>,
+        state: S,
+    ) -> Result<Self> {
+        if format.is_meta() {
+            return Err(Error::msg("Async streams do not support metadata formats"));
+        }
+
+        let dev = device.device_ptr();
+        let timeout_ms = config.timeout().as_millis() as u32;
+        let res = unsafe { bladerf_set_stream_timeout(dev, layout.direction().into(), timeout_ms) };
+        check_res!(res);
+
+        let state = Box::into_raw(Box::new(state));
+        let mut stream = ptr::null_mut();
+        let mut buffers = ptr::null_mut();
+        let res = unsafe {
+            bladerf_init_stream(
+                &mut stream,
+                dev,
+                Some(stream_callback::<S>),
+                &mut buffers,
+                config.num_buffers() as usize,
+                format as bladerf_format,
+                config.samples_per_buffer() as usize,
+                config.num_transfers() as usize,
+                state.cast(),
+            )
+        };
+        if res < 0 {
+            drop(unsafe { Box::from_raw(state) });
+            return Err(Error::from_bladerf_code(res as isize));
+        }
+
+        // RX starts with the first num_transfers buffers in flight, TX asks for them one by one
+        let next = if layout.is_rx() {
+            config.num_transfers() as usize
+        } else {
+            0
+        };
+        // SAFETY: the stream is not running yet, so nothing else accesses the state
+        *unsafe { &mut *state }.ring() = BufferRing {
+            buffers,
+            len: config.num_buffers() as usize,
+            next,
+        };
+
+        let ptrs = StreamPtrs::new(stream, state);
+        if let Err(e) = device.enable_layout(layout) {
+            drop(ptrs);
+            return Err(e);
+        }
+        let thread = thread::spawn(move || ptrs.run(layout));
+
+        Ok(Self {
+            device,
+            layout,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// Whether `bladerf_stream` has returned, after which no more buffers complete
+    fn is_finished(&self) -> bool {
+        self.thread
+            .as_ref()
+            .is_none_or(|thread| thread.is_finished())
+    }
+
+    /// Stop the stream and free its buffers
+    fn stop(&mut self) -> Result<()> {
+        let Some(thread) = self.thread.take() else {
+            return Ok(());
+        };
+        self.stop.store(true, Ordering::Release);
+        // If the thread panicked the stream is leaked rather than freed under a running stream
+        let res = match thread.join() {
+            Ok((ptrs, res)) => {
+                drop(ptrs);
+                res
+            }
+            Err(_) => Err(Error::msg("Streaming thread panicked")),
+        };
+        self.device.disable_layout(self.layout)?;
+        res
+    }
+}
+
+impl Drop for StreamRunner {
+    fn drop(&mut self) {
+        if let Err(e) = self.stop() {
+            warn!("Failed to stop {:?} stream on Drop: {e:?}", self.layout);
+        }
+    }
+}
+
+struct RxState<T> {
+    ring: BufferRing,
+    stop: Arc<AtomicBool>,
+    sender: SyncSender<Vec<T>>,
+    dropped: Arc<AtomicU64>,
+}
+
+// SAFETY: the buffer pointers belong to the stream, which is only driven by one thread
+unsafe impl<T: Send> Send for RxState<T> {}
+
+impl<T: SampleFormat + Copy + Send + 'static> StreamState for RxState<T> {
+    fn ring(&mut self) -> &mut BufferRing {
+        &mut self.ring
+    }
+
+    fn on_buffer(&mut self, samples: *mut c_void, num_samples: usize) -> *mut c_void {
+        if self.stop.load(Ordering::Acquire) {
+            return ptr::null_mut();
+        }
+        if !samples.is_null() {
+            // SAFETY: libbladerf hands back a filled buffer of num_samples samples of the
+            // stream format, which T was checked against
+            let samples = unsafe { slice::from_raw_parts(samples.cast::<T>(), num_samples) };
+            match self.sender.try_send(samples.to_vec()) {
+                Ok(()) => {}
+                Err(TrySendError::Full(_)) => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(TrySendError::Disconnected(_)) => return ptr::null_mut(),
+            }
+        }
+        self.ring.next()
+    }
+}
+
+/// Receive samples asynchronously, with completed buffers delivered over a channel.
+///
+/// Starting the stream enables the channels of the layout, and stopping or dropping it disables
+/// them again. If the application falls more than `num_buffers` buffers behind, new buffers are
+/// dropped and counted in [`AsyncRxStream::dropped_buffers`].
+pub struct AsyncRxStream<T> {
+    runner: StreamRunner,
+    receiver: Receiver<Vec<T>>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl<T: SampleFormat + Copy + Send + 'static> AsyncRxStream<T> {
+    pub fn start(
+        device: Arc<BladeRF>,
+        layout: ChannelLayout,
+        format: Format,
+        config: &StreamConfig,
+    ) -> Result<Self> {
+        if !layout.is_rx() {
+            return Err(Error::msg(format!("{layout:?} is not an RX layout")));
+        }
+        T::check_compatability(format)?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let dropped = Arc::new(AtomicU64::new(0));
+        let (sender, receiver) = mpsc::sync_channel(config.num_buffers() as usize);
+        let state = RxState {
+            ring: BufferRing {
+                buffers: ptr::null_mut(),
+                len: 0,
+                next: 0,
+            },
+            stop: stop.clone(),
+            sender,
+            dropped: dropped.clone(),
+        };
+        let runner = StreamRunner::start(device, layout, format, config, stop, state)?;
+
+        Ok(Self {
+            runner,
+            receiver,
+            dropped,
+        })
+    }
+
+    /// Channel of completed buffers, each holding `samples_per_buffer` samples
+    pub fn receiver(&self) -> &Receiver<Vec<T>> {
+        &self.receiver
+    }
+
+    /// Wait for the next completed buffer
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<Vec<T>> {
+        self.receiver.recv_timeout(timeout).map_err(|e| match e {
+            RecvTimeoutError::Timeout => Error::Timeout,
+            RecvTimeoutError::Disconnected => Error::msg("RX stream has stopped"),
+        })
+    }
+
+    /// Number of buffers dropped because the channel was full
+    pub fn dropped_buffers(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Stop the stream, returning any error it ended with
+    pub fn stop(mut self) -> Result<()> {
+        self.runner.stop()
+    }
+}
+
+/// A stream buffer lent out to the application
+struct BufferPtr {
+    buffer: *mut c_void,
+    num_samples: usize,
+}
+
+// SAFETY: the buffer is owned by the stream and only accessed by whoever holds the pointer
+unsafe impl Send for BufferPtr {}
+
+struct BorrowedRxState {
+    ring: BufferRing,
+    stop: Arc<AtomicBool>,
+    /// Buffers not in flight and not lent out, seeded from the ring on the first callback
+    free: Vec<*mut c_void>,
+    seeded: bool,
+    sender: SyncSender<BufferPtr>,
+    returned: Receiver<BufferPtr>,
+    dropped: Arc<AtomicU64>,
+}
+
+// SAFETY: the buffer pointers belong to the stream, which is only driven by one thread
+unsafe impl Send for BorrowedRxState {}
+
+impl StreamState for BorrowedRxState {
+    fn ring(&mut self) -> &mut BufferRing {
+        &mut self.ring
+    }
+
+    fn on_buffer(&mut self, samples: *mut c_void, num_samples: usize) -> *mut c_void {
+        if self.stop.load(Ordering::Acquire) {
+            return ptr::null_mut();
+        }
+        if !self.seeded {
+            // The ring starts after the buffers libbladerf already has in flight
+            let idle = self.ring.len - self.ring.next;
+            self.free.extend((0..idle).map(|_| self.ring.next()));
+            self.seeded = true;
+        }
+        self.free
+            .extend(self.returned.try_iter().map(|returned| returned.buffer));
+
+        if samples.is_null() {
+            return self.free.pop().unwrap_or(ptr::null_mut());
+        }
+        // Lend the filled buffer out only if another one can take its place in flight,
+        // otherwise its samples are dropped and it is resubmitted as is
+        let Some(next) = self.free.pop() else {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return samples;
+        };
+        let lent = BufferPtr {
+            buffer: samples,
+            num_samples,
+        };
+        match self.sender.try_send(lent) {
+            Ok(()) => next,
+            Err(TrySendError::Full(lent)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                self.free.push(next);
+                lent.buffer
+            }
+            Err(TrySendError::Disconnected(_)) => ptr::null_mut(),
+        }
+    }
+}
+
+/// Receive samples asynchronously without copying them out of libbladerf's stream buffers.
+///
+/// [`BorrowedRxStream::read_borrowed`] hands out a [`SampleGuard`] that derefs to a completed
+/// stream buffer, which goes back to libbladerf once the guard is dropped. This saves the copy
+/// [`AsyncRxStream`] makes of every buffer, at the cost of some constraints:
+///
+/// - A guard borrows the stream, so the stream cannot be stopped or dropped while any guard is
+///   alive. Copy the samples out if they are needed for longer.
+/// - Only `num_buffers - num_transfers` buffers of the [`StreamConfig`] are available to be lent
+///   out. While the application holds all of them, completed buffers are resubmitted and their
+///   samples dropped, as counted by [`BorrowedRxStream::dropped_buffers`]. Release guards
+///   promptly, and size `num_buffers` for the number of guards held at once.
+///
+/// Starting the stream enables the channels of the layout, and stopping or dropping it disables
+/// them again.
+pub struct BorrowedRxStream<T> {
+    runner: StreamRunner,
+    receiver: Receiver<BufferPtr>,
+    release: Sender<BufferPtr>,
+    dropped: Arc<AtomicU64>,
+    _samples: PhantomData<T>,
+}
+
+impl<T: SampleFormat> BorrowedRxStream<T> {
+    pub fn start(
+        device: Arc<BladeRF>,
+        layout: ChannelLayout,
+        format: Format,
+        config: &StreamConfig,
+    ) -> Result<Self> {
+        if !layout.is_rx() {
+            return Err(Error::msg(format!("{layout:?} is not an RX layout")));
+        }
+        T::check_compatability(format)?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let dropped = Arc::new(AtomicU64::new(0));
+        let (sender, receiver) = mpsc::sync_channel(config.num_buffers() as usize);
+        let (release, returned) = mpsc::channel();
+        let state = BorrowedRxState {
+            ring: BufferRing {
+                buffers: ptr::null_mut(),
+                len: 0,
+                next: 0,
+            },
+            stop: stop.clone(),
+            free: Vec::with_capacity(config.num_buffers() as usize),
+            seeded: false,
+            sender,
+            returned,
+            dropped: dropped.clone(),
+        };
+        let runner = StreamRunner::start(device, layout, format, config, stop, state)?;
+
+        Ok(Self {
+            runner,
+            receiver,
+            release,
+            dropped,
+            _samples: PhantomData,
+        })
+    }
+
+    /// Wait for the next completed buffer, borrowed until the returned guard is dropped.
+    ///
+    /// Fails once the stream has ended, e.g. after a USB error or timeout, even if completed
+    /// buffers are still queued. Stop the stream to learn why it ended.
+    pub fn read_borrowed(&self, timeout: Duration) -> Result<SampleGuard<'_, T>> {
+        if self.runner.is_finished() {
+            return Err(Error::msg("RX stream has stopped"));
+        }
+        let lent = self.receiver.recv_timeout(timeout).map_err(|e| match e {
+            RecvTimeoutError::Timeout => Error::Timeout,
+            RecvTimeoutError::Disconnected => Error::msg("RX stream has stopped"),
+        })?;
+        // SAFETY: libbladerf filled the buffer with num_samples samples of the stream format,
+        // which T was checked against. The buffers are only freed by StreamRunner::stop, which
+        // needs the stream the guard borrows, and this one is not resubmitted until the guard
+        // returns it.
+        let samples = unsafe { slice::from_raw_parts(lent.buffer.cast::<T>(), lent.num_samples) };
+
+        Ok(SampleGuard {
+            samples,
+            buffer: Some(lent),
+            release: &self.release,
+        })
+    }
+
+    /// Number of buffers dropped because none could be lent out
+    pub fn dropped_buffers(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Stop the stream, returning any error it ended with
+    pub fn stop(mut self) -> Result<()> {
+        self.runner.stop()
+    }
+}
+
+/// A completed stream buffer lent out by [`BorrowedRxStream::read_borrowed`], returned to the
+/// stream on drop
+pub struct SampleGuard<'a, T> {
+    samples: &'a [T],
+    buffer: Option<BufferPtr>,
+    release: &'a Sender<BufferPtr>,
+}
+
+impl<T> Deref for SampleGuard<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.samples
+    }
+}
+
+impl<T> Drop for SampleGuard<'_, T> {
+    fn drop(&mut self) {
+        if let Some(buffer) = self.buffer.take() {
+            // Fails only once the stream has stopped, which no longer needs the buffer
+            let _ = self.release.send(buffer);
+        }
+    }
+}
+
+struct TxState<T> {
+    ring: BufferRing,
+    stop: Arc<AtomicBool>,
+    receiver: Receiver<Vec<T>>,
+    samples_per_buffer: usize,
+    pending: Vec<T>,
+    offset: usize,
+}
+
+// SAFETY: the buffer pointers belong to the stream, which is only driven by one thread
+unsafe impl<T: Send> Send for TxState<T> {}
+
+impl<T: SampleFormat + Copy + Default + Send + 'static> StreamState for TxState<T> {
+    fn ring(&mut self) -> &mut BufferRing {
+        &mut self.ring
+    }
+
+    fn on_buffer(&mut self, _samples: *mut c_void, _num_samples: usize) -> *mut c_void {
+        if self.stop.load(Ordering::Acquire) {
+            return ptr::null_mut();
+        }
+
+        let buffer = self.ring.next();
+        // SAFETY: every stream buffer holds samples_per_buffer samples of the stream format, and
+        // the ring never hands out a buffer that is still in flight
+        let out = unsafe { slice::from_raw_parts_mut(buffer.cast::<T>(), self.samples_per_buffer) };
+        let mut filled = 0;
+        while filled < out.len() {
+            if self.offset == self.pending.len() {
+                match self.receiver.try_recv() {
+                    Ok(samples) => {
+                        self.pending = samples;
+                        self.offset = 0;
+                        continue;
+                    }
+                    Err(_) => break,
+                }
+            }
+            let n = (out.len() - filled).min(self.pending.len() - self.offset);
+            out[filled..filled + n].copy_from_slice(&self.pending[self.offset..self.offset + n]);
+            filled += n;
+            self.offset += n;
+        }
+        out[filled..].fill(T::default());
+
+        buffer
+    }
+}
+
+/// Transmit samples asynchronously, queued over a channel.
+///
+/// Samples are packed into stream buffers regardless of how they were split between
+/// [`AsyncTxStream::send`] calls. When the queue runs dry the stream keeps running and transmits
+/// zeros until more samples arrive.
+pub struct AsyncTxStream<T> {
+    runner: StreamRunner,
+    sender: SyncSender<Vec<T>>,
+}
+
+impl<T: SampleFormat + Copy + Default + Send + 'static> AsyncTxStream<T> {
+    pub fn start(
+        device: Arc<BladeRF>,
+        layout: ChannelLayout,
+        format: Format,
+        config: &StreamConfig,
+    ) -> Result<Self> {
+        if !layout.is_tx() {
+            return Err(Error::msg(format!("{layout:?} is not a TX layout")));
+        }
+        T::check_compatability(format)?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::sync_channel(config.num_buffers() as usize);
+        let state = TxState {
+            ring: BufferRing {
+                buffers: ptr::null_mut(),
+                len: 0,
+                next: 0,
+            },
+            stop: stop.clone(),
+            receiver,
+            samples_per_buffer: config.samples_per_buffer() as usize,
+            pending: Vec::new(),
+            offset: 0,
+        };
+        let runner = StreamRunner::start(device, layout, format, config, stop, state)?;
+
+        Ok(Self { runner, sender })
+    }
+
+    /// Queue samples for transmission, blocking while the queue is full
+    pub fn send(&self, samples: Vec<T>) -> Result<()> {
+        self.sender
+            .send(samples)
+            .map_err(|_| Error::msg("TX stream has stopped"))
+    }
+
+    /// Stop the stream, returning any error it ended with. Samples still queued are discarded.
+    pub fn stop(mut self) -> Result<()> {
+        self.runner.stop()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_complex::Complex;
+
+    use super::*;
+
+    /// Stands in for the buffers `bladerf_init_stream` allocates, with the first `in_flight` of
+    /// them submitted to libbladerf
+    struct FakeRing {
+        // Backing storage the buffer pointers point into, never read
+        _storage: Vec<u8>,
+        buffers: Vec<*mut c_void>,
+    }
+
+    impl FakeRing {
+        fn new(len: usize) -> Self {
+            let mut storage = vec![0u8; len];
+            let buffers = storage
+                .iter_mut()
+                .map(|byte| (byte as *mut u8).cast())
+                .collect();
+            Self {
+                _storage: storage,
+                buffers,
+            }
+        }
+
+        fn ring(&mut self, in_flight: usize) -> BufferRing {
+            BufferRing {
+                buffers: self.buffers.as_mut_ptr(),
+                len: self.buffers.len(),
+                next: in_flight,
+            }
+        }
+    }
+
+    struct Borrowed {
+        state: BorrowedRxState,
+        stop: Arc<AtomicBool>,
+        dropped: Arc<AtomicU64>,
+        receiver: Receiver<BufferPtr>,
+        release: Sender<BufferPtr>,
+    }
+
+    fn borrowed(ring: BufferRing, capacity: usize) -> Borrowed {
+        let stop = Arc::new(AtomicBool::new(false));
+        let dropped = Arc::new(AtomicU64::new(0));
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+        let (release, returned) = mpsc::channel();
+        let state = BorrowedRxState {
+            ring,
+            stop: stop.clone(),
+            free: Vec::new(),
+            seeded: false,
+            sender,
+            returned,
+            dropped: dropped.clone(),
+        };
+        Borrowed {
+            state,
+            stop,
+            dropped,
+            receiver,
+            release,
+        }
+    }
+
+    #[test]
+    fn borrowed_lends_and_reclaims_buffers() {
+        let mut fake = FakeRing::new(4);
+        let b = fake.buffers.clone();
+        let mut s = borrowed(fake.ring(2), 4);
+
+        // The idle buffers past the two in flight are seeded, and one replaces the lent buffer
+        let next = s.state.on_buffer(b[0], 1024);
+        assert!(next == b[2] || next == b[3]);
+        let lent = s.receiver.try_recv().unwrap();
+        assert_eq!((lent.buffer, lent.num_samples), (b[0], 1024));
+
+        let next2 = s.state.on_buffer(b[1], 1024);
+        assert!((next2 == b[2] || next2 == b[3]) && next2 != next);
+        assert_eq!(s.receiver.try_recv().unwrap().buffer, b[1]);
+        assert_eq!(s.dropped.load(Ordering::Relaxed), 0);
+
+        // Both spare buffers are lent out, so the next one is resubmitted and dropped
+        assert_eq!(s.state.on_buffer(next, 1024), next);
+        assert_eq!(s.dropped.load(Ordering::Relaxed), 1);
+        assert!(s.receiver.try_recv().is_err());
+
+        // A returned buffer takes the place of the next one lent out
+        s.release.send(lent).unwrap();
+        assert_eq!(s.state.on_buffer(next2, 1024), b[0]);
+        assert_eq!(s.receiver.try_recv().unwrap().buffer, next2);
+        assert_eq!(s.dropped.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn borrowed_drops_when_the_queue_is_full() {
+        let mut fake = FakeRing::new(4);
+        let b = fake.buffers.clone();
+        let mut s = borrowed(fake.ring(2), 1);
+
+        s.state.on_buffer(b[0], 1024);
+        // The application has not taken the first buffer yet
+        assert_eq!(s.state.on_buffer(b[1], 1024), b[1]);
+        assert_eq!(s.dropped.load(Ordering::Relaxed), 1);
+
+        // The spare buffer was kept, so lending resumes once the queue drains
+        assert_eq!(s.receiver.try_recv().unwrap().buffer, b[0]);
+        assert!(!s.state.on_buffer(b[1], 1024).is_null());
+        assert_eq!(s.receiver.try_recv().unwrap().buffer, b[1]);
+    }
+
+    #[test]
+    fn borrowed_stops() {
+        let mut fake = FakeRing::new(4);
+        let b = fake.buffers.clone();
+        let s = borrowed(fake.ring(2), 4);
+        let mut state = s.state;
+        s.stop.store(true, Ordering::Release);
+        assert!(state.on_buffer(b[0], 1024).is_null());
+
+        // Dropping the stream's receiver shuts the stream down too
+        let mut s = borrowed(fake.ring(2), 4);
+        drop(s.receiver);
+        assert!(s.state.on_buffer(b[0], 1024).is_null());
+    }
+
+    #[test]
+    fn borrowed_shutdown_with_buffers_queued() {
+        let mut fake = FakeRing::new(4);
+        let b = fake.buffers.clone();
+        let mut s = borrowed(fake.ring(2), 4);
+
+        s.state.on_buffer(b[0], 1024);
+        s.state.on_buffer(b[1], 1024);
+        let held = s.receiver.try_recv().unwrap();
+
+        // The stream ends with one buffer lent out and one still queued
+        s.stop.store(true, Ordering::Release);
+        assert!(s.state.on_buffer(b[2], 1024).is_null());
+        drop(s.state);
+
+        // Both still refer to the ring's buffers, which the stream frees only once stopped
+        assert_eq!(held.buffer, b[0]);
+        assert_eq!(s.receiver.try_recv().unwrap().buffer, b[1]);
+
+        // Returning a buffer to a stream that has ended is not an error
+        let guard = SampleGuard::<u8> {
+            samples: &[],
+            buffer: Some(held),
+            release: &s.release,
+        };
+        drop(guard);
+    }
+
+    #[test]
+    fn sample_guard_returns_its_buffer() {
+        let mut storage = [Complex::<i16>::new(1, -1); 4];
+        let buffer: *mut c_void = storage.as_mut_ptr().cast();
+        let (release, returned) = mpsc::channel();
+        {
+            let guard = SampleGuard {
+                samples: &storage[..],
+                buffer: Some(BufferPtr {
+                    buffer,
+                    num_samples: 4,
+                }),
+                release: &release,
+            };
+            assert_eq!(guard.len(), 4);
+            assert_eq!(guard[3], Complex::new(1, -1));
+            assert!(returned.try_recv().is_err());
+        }
+        assert_eq!(returned.try_recv().unwrap().buffer, buffer);
+    }
+}
@@ -0,0 +1,56 @@
+//! Awaitable wrappers around the synchronous interface, enabled by the `tokio` feature
+//!
+//! `bladerf_sync_rx`/`bladerf_sync_tx` block until the transfer completes, so each call is moved
+//! onto tokio's blocking thread pool. The sample buffer is owned by the call while it is in
+//! flight and handed back once it completes.
+
+use std::{future::Future, sync::Arc, time::Duration};
+
+use crate::{BladeRF, Error, Metadata, Result, SampleFormat};
+
+impl BladeRF {
+    /// Receive IQ samples on tokio's blocking thread pool, see [`BladeRF::sync_rx`].
+    ///
+    /// Returns the filled buffer along with the updated metadata, if any was passed in.
+    pub fn sync_rx_async<T>(
+        self: &Arc<Self>,
+        mut data: Vec<T>,
+        mut metadata: Option<Metadata>,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<(Vec<T>, Option<Metadata>)>>
+    where
+        T: SampleFormat + Send + 'static,
+    {
+        let device = Arc::clone(self);
+        let task = tokio::task::spawn_blocking(move || {
+            device.sync_rx(&mut data, metadata.as_mut(), timeout)?;
+            Ok((data, metadata))
+        });
+        async move { task.await.map_err(join_error)? }
+    }
+
+    /// Transmit IQ samples on tokio's blocking thread pool, see [`BladeRF::sync_tx`].
+    ///
+    /// Returns the buffer so it can be refilled, along with the updated metadata, if any was
+    /// passed in.
+    pub fn sync_tx_async<T>(
+        self: &Arc<Self>,
+        data: Vec<T>,
+        mut metadata: Option<Metadata>,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<(Vec<T>, Option<Metadata>)>>
+    where
+        T: SampleFormat + Send + 'static,
+    {
+        let device = Arc::clone(self);
+        let task = tokio::task::spawn_blocking(move || {
+            device.sync_tx(&data, metadata.as_mut(), timeout)?;
+            Ok((data, metadata))
+        });
+        async move { task.await.map_err(join_error)? }
+    }
+}
+
+fn join_error(e: tokio::task::JoinError) -> Error {
+    Error::msg(format!("Blocking sync transfer failed to complete: {e}"))
+}
@@ -0,0 +1,38 @@
+use crate::{Error, Result};
+
+/// Board family of a bladeRF, see [`crate::BladeRF::board_kind`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BoardKind {
+    /// bladeRF x40/x115, with one RX and one TX channel
+    BladeRf1,
+    /// bladeRF 2.0 micro xA4/xA9, with two RX and two TX channels
+    BladeRf2,
+}
+
+impl BoardKind {
+    /// Board name as reported by [`crate::BladeRF::get_board_name`]
+    pub fn name(&self) -> &'static str {
+        match self {
+            BoardKind::BladeRf1 => "bladerf1",
+            BoardKind::BladeRf2 => "bladerf2",
+        }
+    }
+}
+
+impl std::fmt::Display for BoardKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl TryFrom<&str> for BoardKind {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        match value {
+            "bladerf1" => Ok(BoardKind::BladeRf1),
+            "bladerf2" => Ok(BoardKind::BladeRf2),
+            _ => Err(Error::msg(format!("Invalid BoardKind value: {value}"))),
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use enum_map::Enum;
 use strum::FromRepr;
 
-use crate::{sys::*, Error, Result};
+use crate::{sys::*, BoardKind, Direction, Error, Result};
 
 /// Determined from the bladerf channel macros defined in
 /// <https://www.nuand.com/libbladeRF-doc/v2.5.0/group___f_n___c_h_a_n_n_e_l.html#ga832d79e0f128448d2258bd11a39bd45d>
@@ -21,6 +21,62 @@ impl Channel {
     pub fn is_tx(&self) -> bool {
         matches!(self, Channel::Tx0 | Channel::Tx1)
     }
+
+    /// RX channels present on `board`
+    pub fn rx_channels(board: BoardKind) -> &'static [Channel] {
+        match board {
+            BoardKind::BladeRf1 => &[Channel::Rx0],
+            BoardKind::BladeRf2 => &[Channel::Rx0, Channel::Rx1],
+        }
+    }
+
+    /// TX channels present on `board`
+    pub fn tx_channels(board: BoardKind) -> &'static [Channel] {
+        match board {
+            BoardKind::BladeRf1 => &[Channel::Tx0],
+            BoardKind::BladeRf2 => &[Channel::Tx0, Channel::Tx1],
+        }
+    }
+
+    /// Every channel present on `board`, RX channels first
+    pub fn all(board: BoardKind) -> &'static [Channel] {
+        match board {
+            BoardKind::BladeRf1 => &[Channel::Rx0, Channel::Tx0],
+            BoardKind::BladeRf2 => &[Channel::Rx0, Channel::Rx1, Channel::Tx0, Channel::Tx1],
+        }
+    }
+
+    /// Channel number `index` in direction `dir`, or None if the index is out of range
+    pub fn from_direction_index(dir: Direction, index: usize) -> Option<Channel> {
+        match (dir, index) {
+            (Direction::RX, 0) => Some(Channel::Rx0),
+            (Direction::RX, 1) => Some(Channel::Rx1),
+            (Direction::TX, 0) => Some(Channel::Tx0),
+            (Direction::TX, 1) => Some(Channel::Tx1),
+            _ => None,
+        }
+    }
+
+    /// Direction and per-direction index of this channel
+    pub fn to_direction_index(&self) -> (Direction, usize) {
+        match self {
+            Channel::Rx0 => (Direction::RX, 0),
+            Channel::Rx1 => (Direction::RX, 1),
+            Channel::Tx0 => (Direction::TX, 0),
+            Channel::Tx1 => (Direction::TX, 1),
+        }
+    }
+}
+
+impl std::fmt::Display for Channel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Channel::Rx0 => "RX0",
+            Channel::Rx1 => "RX1",
+            Channel::Tx0 => "TX0",
+            Channel::Tx1 => "TX1",
+        })
+    }
 }
 
 impl TryFrom<bladerf_channel> for Channel {
@@ -30,3 +86,48 @@ impl TryFrom<bladerf_channel> for Channel {
         Self::from_repr(channel).ok_or_else(|| format!("Invalid bladerf channel: {channel}").into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn direction_index_mapping() {
+        assert_eq!(
+            Channel::from_direction_index(Direction::RX, 0),
+            Some(Channel::Rx0)
+        );
+        assert_eq!(
+            Channel::from_direction_index(Direction::TX, 1),
+            Some(Channel::Tx1)
+        );
+        assert_eq!(Channel::from_direction_index(Direction::RX, 2), None);
+        assert_eq!(Channel::from_direction_index(Direction::TX, 2), None);
+
+        assert_eq!(Channel::Rx0.to_direction_index(), (Direction::RX, 0));
+        assert_eq!(Channel::Tx1.to_direction_index(), (Direction::TX, 1));
+
+        for channel in [Channel::Rx0, Channel::Rx1, Channel::Tx0, Channel::Tx1] {
+            let (dir, index) = channel.to_direction_index();
+            assert_eq!(Channel::from_direction_index(dir, index), Some(channel));
+        }
+    }
+
+    #[test]
+    fn board_channels() {
+        for board in [BoardKind::BladeRf1, BoardKind::BladeRf2] {
+            let rx = Channel::rx_channels(board);
+            let tx = Channel::tx_channels(board);
+            assert!(rx.iter().all(Channel::is_rx));
+            assert!(tx.iter().all(Channel::is_tx));
+            assert_eq!(Channel::all(board), [rx, tx].concat());
+            assert_eq!(BoardKind::try_from(board.name()), Ok(board));
+        }
+        assert_eq!(
+            Channel::all(BoardKind::BladeRf1),
+            [Channel::Rx0, Channel::Tx0]
+        );
+        assert_eq!(Channel::all(BoardKind::BladeRf2).len(), 4);
+        assert!(BoardKind::try_from("bladerf3").is_err());
+    }
+}
@@ -0,0 +1,22 @@
+use strum::FromRepr;
+
+use crate::{sys::*, Error, Result};
+
+/// Source of the bladeRF2 system clock
+#[derive(Copy, Clone, Debug, FromRepr, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ClockSelect {
+    /// The onboard VCTCXO
+    Onboard = bladerf_clock_select_CLOCK_SELECT_ONBOARD,
+    /// A 38.4 MHz clock fed into the clock input connector
+    External = bladerf_clock_select_CLOCK_SELECT_EXTERNAL,
+}
+
+impl TryFrom<bladerf_clock_select> for ClockSelect {
+    type Error = Error;
+
+    fn try_from(value: bladerf_clock_select) -> Result<Self> {
+        Self::from_repr(value)
+            .ok_or_else(|| Error::msg(format!("Invalid ClockSelect value: {value}")))
+    }
+}
@@ -0,0 +1,26 @@
+use strum::FromRepr;
+
+use crate::{sys::*, Error, Result};
+
+/// Blocks of the bladeRF1 LMS6002D that can be DC offset calibrated, see
+/// [`crate::BladeRF::calibrate_dc`].
+///
+/// libbladerf has no TXVGA2 calibration, so the TX path is covered by [`DcCalModule::TxLpf`].
+#[derive(Copy, Clone, Debug, FromRepr, PartialEq, Eq)]
+#[repr(i32)]
+pub enum DcCalModule {
+    /// Low-pass filter tuning module, shared by RX and TX
+    LpfTuning = bladerf_cal_module_BLADERF_DC_CAL_LPF_TUNING,
+    TxLpf = bladerf_cal_module_BLADERF_DC_CAL_TX_LPF,
+    RxLpf = bladerf_cal_module_BLADERF_DC_CAL_RX_LPF,
+    RxVga2 = bladerf_cal_module_BLADERF_DC_CAL_RXVGA2,
+}
+
+impl TryFrom<bladerf_cal_module> for DcCalModule {
+    type Error = Error;
+
+    fn try_from(value: bladerf_cal_module) -> Result<Self> {
+        Self::from_repr(value)
+            .ok_or_else(|| Error::msg(format!("Invalid DcCalModule value: {value}")))
+    }
+}
@@ -1,12 +1,34 @@
-use crate::{sys::*, BladeRF, Result};
+use crate::{sys::*, BladeRF, Error, Result};
 use bytemuck::cast_slice;
+use std::hash::{Hash, Hasher};
 
 use super::Backend;
 
 /// Information about a bladerf device connect to the system
+///
+/// Equality and hashing only consider the serial number and USB bus/address, so the same device
+/// compares equal across enumerations even if transient fields such as the instance differ.
 #[derive(Clone, Debug)]
 pub struct DevInfo(pub(crate) bladerf_devinfo);
 
+impl PartialEq for DevInfo {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.serial == other.0.serial
+            && self.0.usb_bus == other.0.usb_bus
+            && self.0.usb_addr == other.0.usb_addr
+    }
+}
+
+impl Eq for DevInfo {}
+
+impl Hash for DevInfo {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.serial.hash(state);
+        self.0.usb_bus.hash(state);
+        self.0.usb_addr.hash(state);
+    }
+}
+
 impl DevInfo {
     pub fn backend(&self) -> Result<Backend> {
         self.0.backend.try_into()
@@ -31,13 +53,145 @@ impl DevInfo {
         String::from_utf8_lossy(cast_slice(&self.0.product)).to_string()
     }
 
+    /// Start building a devinfo to open a device by serial, backend, USB location or instance,
+    /// without listing devices first.
+    ///
+    /// ```no_run
+    /// # fn main() -> bladerf::Result<()> {
+    /// let devinfo = bladerf::DevInfo::builder().serial("deadbeef").build()?;
+    /// let device = bladerf::BladeRF::open_with_devinfo(&devinfo)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn builder() -> DevInfoBuilder {
+        DevInfoBuilder::default()
+    }
+
     pub fn open(&self) -> Result<BladeRF> {
         BladeRF::open_with_devinfo(self)
     }
 }
 
+/// Builds a [`DevInfo`] matching devices by the fields that are set, see [`DevInfo::builder`]
+#[derive(Clone, Debug, Default)]
+pub struct DevInfoBuilder {
+    backend: Option<Backend>,
+    serial: Option<String>,
+    usb_bus: Option<u8>,
+    usb_addr: Option<u8>,
+    instance: Option<u32>,
+}
+
+impl DevInfoBuilder {
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    /// Match devices whose serial number starts with `serial`
+    pub fn serial(mut self, serial: impl Into<String>) -> Self {
+        self.serial = Some(serial.into());
+        self
+    }
+
+    pub fn usb_bus(mut self, usb_bus: u8) -> Self {
+        self.usb_bus = Some(usb_bus);
+        self
+    }
+
+    pub fn usb_addr(mut self, usb_addr: u8) -> Self {
+        self.usb_addr = Some(usb_addr);
+        self
+    }
+
+    pub fn instance(mut self, instance: u32) -> Self {
+        self.instance = Some(instance);
+        self
+    }
+
+    /// Build the devinfo, with every field that was not set matching any device.
+    ///
+    /// Fails if the serial is longer than a full serial number or contains a NUL.
+    pub fn build(self) -> Result<DevInfo> {
+        let serial = match &self.serial {
+            Some(serial) => {
+                if serial.len() > crate::serial_length() || serial.contains('\0') {
+                    return Err(Error::msg(format!(
+                        "Invalid serial `{serial}`, expected up to {} characters",
+                        crate::serial_length()
+                    )));
+                }
+                Some(serial.as_bytes())
+            }
+            None => None,
+        };
+
+        // SAFETY: zeroed devinfo is plain data, and init_devinfo overwrites it with wildcards
+        let mut info: bladerf_devinfo = unsafe { std::mem::zeroed() };
+        unsafe { bladerf_init_devinfo(&mut info) };
+
+        if let Some(backend) = self.backend {
+            info.backend = backend.into();
+        }
+        if let Some(serial) = serial {
+            info.serial = [0; BLADERF_SERIAL_LENGTH as usize];
+            for (dst, src) in info.serial.iter_mut().zip(serial) {
+                *dst = *src as _;
+            }
+        }
+        if let Some(usb_bus) = self.usb_bus {
+            info.usb_bus = usb_bus;
+        }
+        if let Some(usb_addr) = self.usb_addr {
+            info.usb_addr = usb_addr;
+        }
+        if let Some(instance) = self.instance {
+            info.instance = instance;
+        }
+
+        Ok(DevInfo(info))
+    }
+}
+
 impl From<bladerf_devinfo> for DevInfo {
     fn from(dev: bladerf_devinfo) -> Self {
         Self(dev)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn devinfo(serial: u8, instance: u32) -> DevInfo {
+        let mut serial_bytes = [0; 33];
+        serial_bytes[0] = serial as _;
+        DevInfo(bladerf_devinfo {
+            backend: 0,
+            serial: serial_bytes,
+            usb_bus: 1,
+            usb_addr: 4,
+            instance,
+            manufacturer: [0; 33],
+            product: [0; 33],
+        })
+    }
+
+    #[test]
+    fn devinfo_builder_rejects_bad_serial() {
+        assert!(DevInfo::builder().serial("0".repeat(33)).build().is_err());
+        assert!(DevInfo::builder().serial("dead\0beef").build().is_err());
+    }
+
+    #[test]
+    fn devinfo_identity() {
+        assert_eq!(devinfo(b'a', 0), devinfo(b'a', 1));
+        assert_ne!(devinfo(b'a', 0), devinfo(b'b', 0));
+
+        let set: HashSet<DevInfo> = [devinfo(b'a', 0), devinfo(b'a', 1), devinfo(b'b', 0)]
+            .into_iter()
+            .collect();
+        assert_eq!(set.len(), 2);
+    }
+}
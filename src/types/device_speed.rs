@@ -0,0 +1,23 @@
+use strum::FromRepr;
+
+use crate::{sys::*, Error, Result};
+
+/// USB bus speed the device is connected at
+#[derive(Copy, Clone, Debug, FromRepr, PartialEq, Eq)]
+#[repr(u32)]
+pub enum DeviceSpeed {
+    Unknown = bladerf_dev_speed_BLADERF_DEVICE_SPEED_UNKNOWN,
+    /// USB 2.0 Hi-Speed
+    HighSpeed = bladerf_dev_speed_BLADERF_DEVICE_SPEED_HIGH,
+    /// USB 3.0 SuperSpeed
+    SuperSpeed = bladerf_dev_speed_BLADERF_DEVICE_SPEED_SUPER,
+}
+
+impl TryFrom<bladerf_dev_speed> for DeviceSpeed {
+    type Error = Error;
+
+    fn try_from(value: bladerf_dev_speed) -> Result<Self> {
+        Self::from_repr(value)
+            .ok_or_else(|| Error::msg(format!("Invalid DeviceSpeed value: {value}")))
+    }
+}
@@ -10,6 +10,15 @@ pub enum Direction {
     TX = bladerf_direction_BLADERF_TX,
 }
 
+impl std::fmt::Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Direction::RX => "RX",
+            Direction::TX => "TX",
+        })
+    }
+}
+
 impl From<Direction> for bladerf_direction {
     fn from(dir: Direction) -> Self {
         dir as bladerf_direction
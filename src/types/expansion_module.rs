@@ -0,0 +1,29 @@
+use strum::FromRepr;
+
+use crate::{sys::*, Error, Result};
+
+/// Expansion boards that can be attached to the bladeRF
+///
+/// Query and attach them with [`crate::BladeRF::get_attached_expansion`] and
+/// [`crate::BladeRF::expansion_attach`] on any board. The expansion boards themselves are made
+/// for the bladeRF1 expansion header; libbladerf does not support them on the bladeRF2.
+#[derive(Copy, Clone, Debug, FromRepr, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ExpansionModule {
+    None = bladerf_xb_BLADERF_XB_NONE,
+    /// XB-100 GPIO expansion board
+    Xb100 = bladerf_xb_BLADERF_XB_100,
+    /// XB-200 transverter board
+    Xb200 = bladerf_xb_BLADERF_XB_200,
+    /// XB-300 amplifier board
+    Xb300 = bladerf_xb_BLADERF_XB_300,
+}
+
+impl TryFrom<bladerf_xb> for ExpansionModule {
+    type Error = Error;
+
+    fn try_from(value: bladerf_xb) -> Result<Self> {
+        Self::from_repr(value)
+            .ok_or_else(|| Error::msg(format!("Invalid ExpansionModule value: {value}")))
+    }
+}
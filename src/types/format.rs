@@ -11,13 +11,32 @@ pub enum Format {
     Sc16Q11 = bladerf_format_BLADERF_FORMAT_SC16_Q11,
     #[doc = "[`bladerf_format_BLADERF_FORMAT_SC8_Q7`]"]
     Sc8Q7 = bladerf_format_BLADERF_FORMAT_SC8_Q7,
-    // TODO: implement meta parsing
-    // #[doc = "[`bladerf_format_BLADERF_FORMAT_SC16_Q11_META`]"]
-    // Sc16Q11Meta = bladerf_format_BLADERF_FORMAT_SC16_Q11_META,
+    /// Same samples as [`Format::Sc16Q11`], with a [`crate::Metadata`] passed alongside every
+    /// transfer for timestamps and burst control
+    #[doc = "[`bladerf_format_BLADERF_FORMAT_SC16_Q11_META`]"]
+    Sc16Q11Meta = bladerf_format_BLADERF_FORMAT_SC16_Q11_META,
+    /// Same samples as [`Format::Sc8Q7`], with a [`crate::Metadata`] passed alongside every
+    /// transfer for timestamps and burst control
+    #[doc = "[`bladerf_format_BLADERF_FORMAT_SC8_Q7_META`]"]
+    Sc8Q7Meta = bladerf_format_BLADERF_FORMAT_SC8_Q7_META,
+    // TODO: implement packet parsing
     // #[doc = "[`bladerf_format_BLADERF_FORMAT_PACKET_META`]"]
     // PacketMeta = bladerf_format_BLADERF_FORMAT_PACKET_META,
-    // #[doc = "[`bladerf_format_BLADERF_FORMAT_SC8_Q7_META`]"]
-    // Sc8Q7Meta = bladerf_format_BLADERF_FORMAT_SC8_Q7_META,
+}
+
+impl Format {
+    /// True for formats that require a [`crate::Metadata`] with every transfer
+    pub fn is_meta(&self) -> bool {
+        matches!(self, Format::Sc16Q11Meta | Format::Sc8Q7Meta)
+    }
+
+    /// Size of one I/Q sample in bytes
+    pub fn sample_size(&self) -> usize {
+        match self {
+            Format::Sc16Q11 | Format::Sc16Q11Meta => 4,
+            Format::Sc8Q7 | Format::Sc8Q7Meta => 2,
+        }
+    }
 }
 
 impl TryFrom<bladerf_format> for Format {
@@ -34,8 +53,8 @@ impl TryFrom<bladerf_format> for Format {
 /// `is_compatible` must only return true if it is valid to re-interpret bytes from the device as `Self`.
 ///
 /// Currently this is only implemented for:
-/// - `Format::Sc16Q11` => `Complex<i16>`
-/// - `Format::Sc8Q7` => `Complex<i8>`
+/// - `Format::Sc16Q11`, `Format::Sc16Q11Meta` => `Complex<i16>`, [`RawSc16`]
+/// - `Format::Sc8Q7`, `Format::Sc8Q7Meta` => `Complex<i8>`, [`RawSc8`]
 pub unsafe trait SampleFormat: Sized {
     /// Returns true if this data type is commutable with the given format enum
     fn is_compatible(format: Format) -> bool;
@@ -55,12 +74,196 @@ pub unsafe trait SampleFormat: Sized {
 // Implementations for supported types
 unsafe impl SampleFormat for Complex<i16> {
     fn is_compatible(format: Format) -> bool {
-        matches!(format, Format::Sc16Q11)
+        matches!(format, Format::Sc16Q11 | Format::Sc16Q11Meta)
     }
 }
 
 unsafe impl SampleFormat for Complex<i8> {
     fn is_compatible(format: Format) -> bool {
-        matches!(format, Format::Sc8Q7)
+        matches!(format, Format::Sc8Q7 | Format::Sc8Q7Meta)
+    }
+}
+
+/// One SC16 Q11 sample as the raw `[I, Q]` pair sent over the wire.
+///
+/// A `&[RawSc16]` has the same layout as a flat interleaved `&[i16]` (`I0, Q0, I1, Q1, ...`), so
+/// it can be handed to DSP code expecting interleaved buffers via [`bytemuck::cast_slice`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct RawSc16(pub [i16; 2]);
+
+/// One SC8 Q7 sample as the raw `[I, Q]` pair sent over the wire.
+///
+/// See [`RawSc16`] for the interleave order.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct RawSc8(pub [i8; 2]);
+
+// SAFETY: both are `repr(transparent)` wrappers around arrays of plain integers
+unsafe impl bytemuck::Zeroable for RawSc16 {}
+unsafe impl bytemuck::Pod for RawSc16 {}
+unsafe impl bytemuck::Zeroable for RawSc8 {}
+unsafe impl bytemuck::Pod for RawSc8 {}
+
+unsafe impl SampleFormat for RawSc16 {
+    fn is_compatible(format: Format) -> bool {
+        matches!(format, Format::Sc16Q11 | Format::Sc16Q11Meta)
+    }
+}
+
+unsafe impl SampleFormat for RawSc8 {
+    fn is_compatible(format: Format) -> bool {
+        matches!(format, Format::Sc8Q7 | Format::Sc8Q7Meta)
+    }
+}
+
+/// Full scale of an SC16 Q11 component: samples range over `[-2048, 2048)`
+const SC16_Q11_SCALE: f32 = 2048.0;
+
+/// Convert an SC16 Q11 sample to floating point, scaled to `[-1.0, 1.0)`
+pub fn brf_ci16_to_cf32(sample: Complex<i16>) -> Complex<f32> {
+    Complex::new(
+        sample.re as f32 / SC16_Q11_SCALE,
+        sample.im as f32 / SC16_Q11_SCALE,
+    )
+}
+
+/// Convert a floating point sample in `[-1.0, 1.0]` to SC16 Q11, saturating values outside it
+pub fn brf_cf32_to_ci16(sample: Complex<f32>) -> Complex<i16> {
+    let convert = |x: f32| (x * SC16_Q11_SCALE).round().clamp(-2048.0, 2047.0) as i16;
+    Complex::new(convert(sample.re), convert(sample.im))
+}
+
+/// Flat `[re, im, re, im, ...]` view of complex samples
+fn flatten<T>(samples: &[Complex<T>]) -> &[T] {
+    // SAFETY: Complex<T> is repr(C) with two T fields and no padding
+    unsafe { std::slice::from_raw_parts(samples.as_ptr().cast(), samples.len() * 2) }
+}
+
+fn flatten_mut<T>(samples: &mut [Complex<T>]) -> &mut [T] {
+    // SAFETY: as for flatten, and the borrow is exclusive
+    unsafe { std::slice::from_raw_parts_mut(samples.as_mut_ptr().cast(), samples.len() * 2) }
+}
+
+/// Convert a buffer of SC16 Q11 samples to floating point, see [`brf_ci16_to_cf32`].
+///
+/// Works on the interleaved components in a single loop so the compiler can vectorize it.
+///
+/// # Panics
+/// If `src` and `dst` have different lengths.
+pub fn brf_ci16_slice_to_cf32(src: &[Complex<i16>], dst: &mut [Complex<f32>]) {
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "source and destination lengths differ"
+    );
+    let src = flatten(src);
+    let dst = flatten_mut(dst);
+
+    for (out, x) in dst.iter_mut().zip(src) {
+        *out = *x as f32 * (1.0 / SC16_Q11_SCALE);
+    }
+}
+
+/// Convert a buffer of floating point samples to SC16 Q11, see [`brf_cf32_to_ci16`].
+///
+/// # Panics
+/// If `src` and `dst` have different lengths.
+pub fn brf_cf32_slice_to_ci16(src: &[Complex<f32>], dst: &mut [Complex<i16>]) {
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "source and destination lengths differ"
+    );
+    let src = flatten(src);
+    let dst = flatten_mut(dst);
+
+    for (out, x) in dst.iter_mut().zip(src) {
+        *out = (*x * SC16_Q11_SCALE).round().clamp(-2048.0, 2047.0) as i16;
+    }
+}
+
+/// View SC16 Q11 samples as the little-endian bytes the device sends and expects.
+#[cfg(target_endian = "little")]
+pub fn as_byte_slice(samples: &[Complex<i16>]) -> &[u8] {
+    // SAFETY: Complex<i16> is repr(C) with two i16 fields and no padding, any byte is a valid u8,
+    // and the length covers exactly the same memory
+    unsafe { std::slice::from_raw_parts(samples.as_ptr().cast(), std::mem::size_of_val(samples)) }
+}
+
+/// View little-endian SC16 Q11 bytes, e.g. read from a capture file, as samples.
+///
+/// Fails if `bytes` is not a whole number of samples or is not aligned for `i16`.
+#[cfg(target_endian = "little")]
+pub fn from_byte_slice(bytes: &[u8]) -> Result<&[Complex<i16>]> {
+    const SAMPLE_SIZE: usize = std::mem::size_of::<Complex<i16>>();
+
+    if !bytes.len().is_multiple_of(SAMPLE_SIZE) {
+        return Err(Error::msg(format!(
+            "Byte slice of length {} is not a multiple of the {SAMPLE_SIZE} byte sample size",
+            bytes.len()
+        )));
+    }
+    if bytes
+        .as_ptr()
+        .align_offset(std::mem::align_of::<Complex<i16>>())
+        != 0
+    {
+        return Err(Error::msg("Byte slice is not aligned for Complex<i16>"));
+    }
+    // SAFETY: length and alignment checked above, and every bit pattern is a valid i16
+    Ok(unsafe { std::slice::from_raw_parts(bytes.as_ptr().cast(), bytes.len() / SAMPLE_SIZE) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_sc16_interleave() {
+        let samples = [RawSc16([1, 2]), RawSc16([3, 4])];
+        let flat: &[i16] = bytemuck::cast_slice(&samples);
+        assert_eq!(flat, &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn cf32_conversion() {
+        let sample = brf_ci16_to_cf32(Complex::new(1024, -2048));
+        assert_eq!(sample, Complex::new(0.5, -1.0));
+        assert_eq!(brf_cf32_to_ci16(sample), Complex::new(1024, -2048));
+        assert_eq!(
+            brf_cf32_to_ci16(Complex::new(1.0, -1.5)),
+            Complex::new(2047, -2048)
+        );
+    }
+
+    #[test]
+    fn cf32_slice_conversion() {
+        let wire: Vec<_> = (-2048..2048)
+            .step_by(7)
+            .map(|x| Complex::new(x, -x.max(-2047)))
+            .collect();
+        let mut float = vec![Complex::default(); wire.len()];
+        brf_ci16_slice_to_cf32(&wire, &mut float);
+        for (w, f) in wire.iter().zip(&float) {
+            assert_eq!(brf_ci16_to_cf32(*w), *f);
+        }
+
+        let mut back = vec![Complex::default(); wire.len()];
+        brf_cf32_slice_to_ci16(&float, &mut back);
+        assert_eq!(back, wire);
+    }
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn byte_slice_round_trip() {
+        let samples = [Complex::new(1i16, -2), Complex::new(0x1234, 0)];
+        let bytes = as_byte_slice(&samples);
+        assert_eq!(bytes, &[1, 0, 0xfe, 0xff, 0x34, 0x12, 0, 0]);
+        assert_eq!(from_byte_slice(bytes).unwrap(), &samples);
+
+        assert!(from_byte_slice(&bytes[..6]).is_err());
+        // Offset by one byte to break alignment
+        assert!(from_byte_slice(&bytes[1..5]).is_err());
     }
 }
@@ -0,0 +1,42 @@
+use strum::FromRepr;
+
+use crate::{sys::*, Error, Result};
+
+/// FPGA device variant fitted to the board
+#[derive(Copy, Clone, Debug, FromRepr, PartialEq, Eq)]
+#[repr(u32)]
+pub enum FpgaSize {
+    Unknown = bladerf_fpga_size_BLADERF_FPGA_UNKNOWN,
+    /// 40 kLE Cyclone IV (bladeRF1 x40)
+    Kle40 = bladerf_fpga_size_BLADERF_FPGA_40KLE,
+    /// 115 kLE Cyclone IV (bladeRF1 x115)
+    Kle115 = bladerf_fpga_size_BLADERF_FPGA_115KLE,
+    /// 49 kLE Cyclone V (bladeRF2 A4)
+    A4 = bladerf_fpga_size_BLADERF_FPGA_A4,
+    /// 77 kLE Cyclone V (bladeRF2 A5)
+    A5 = bladerf_fpga_size_BLADERF_FPGA_A5,
+    /// 301 kLE Cyclone V (bladeRF2 A9)
+    A9 = bladerf_fpga_size_BLADERF_FPGA_A9,
+}
+
+impl TryFrom<bladerf_fpga_size> for FpgaSize {
+    type Error = Error;
+
+    fn try_from(value: bladerf_fpga_size) -> Result<Self> {
+        Self::from_repr(value).ok_or_else(|| Error::msg(format!("Invalid FpgaSize value: {value}")))
+    }
+}
+
+/// Size in bytes of an uncompressed bitstream for the given FPGA, or `None` if unknown
+///
+/// Matches the sizes libbladerf checks bitstreams against before loading or flashing them.
+pub fn fpga_size_bytes(size: FpgaSize) -> Option<usize> {
+    match size {
+        FpgaSize::Unknown => None,
+        FpgaSize::Kle40 => Some(1191788),
+        FpgaSize::Kle115 => Some(3571462),
+        FpgaSize::A4 => Some(2632660),
+        FpgaSize::A5 => Some(4244820),
+        FpgaSize::A9 => Some(12858972),
+    }
+}
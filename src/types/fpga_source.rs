@@ -0,0 +1,23 @@
+use strum::FromRepr;
+
+use crate::{sys::*, Error, Result};
+
+/// Where the currently loaded FPGA bitstream came from
+#[derive(Copy, Clone, Debug, FromRepr, PartialEq, Eq)]
+#[repr(u32)]
+pub enum FpgaSource {
+    Unknown = bladerf_fpga_source_BLADERF_FPGA_SOURCE_UNKNOWN,
+    /// Autoloaded from SPI flash at power on, so it persists across power cycles
+    Flash = bladerf_fpga_source_BLADERF_FPGA_SOURCE_FLASH,
+    /// Loaded by the host, and lost when the device is power cycled
+    Host = bladerf_fpga_source_BLADERF_FPGA_SOURCE_HOST,
+}
+
+impl TryFrom<bladerf_fpga_source> for FpgaSource {
+    type Error = Error;
+
+    fn try_from(value: bladerf_fpga_source) -> Result<Self> {
+        Self::from_repr(value)
+            .ok_or_else(|| Error::msg(format!("Invalid FpgaSource value: {value}")))
+    }
+}
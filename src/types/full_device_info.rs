@@ -0,0 +1,37 @@
+use std::fmt;
+
+use super::{DevInfo, DeviceSpeed, FpgaSize, Version};
+
+/// Everything commonly needed to describe an open device, see [`crate::BladeRF::full_info`]
+#[derive(Clone, Debug)]
+pub struct FullDeviceInfo {
+    pub dev_info: DevInfo,
+    pub board_name: String,
+    pub serial: String,
+    pub speed: DeviceSpeed,
+    pub firmware_version: Version,
+    pub fpga_configured: bool,
+    /// None if the FPGA is not configured
+    pub fpga_version: Option<Version>,
+    pub fpga_size: FpgaSize,
+}
+
+impl fmt::Display for FullDeviceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Board: {}", self.board_name)?;
+        writeln!(f, "  Serial: {}", self.serial)?;
+        writeln!(
+            f,
+            "  USB bus/address: {}/{}",
+            self.dev_info.0.usb_bus, self.dev_info.0.usb_addr
+        )?;
+        writeln!(f, "  USB speed: {:?}", self.speed)?;
+        writeln!(f, "  Firmware Version: {}", self.firmware_version)?;
+        writeln!(f, "  FPGA Size: {:?}", self.fpga_size)?;
+        writeln!(f, "  FPGA Configured: {}", self.fpga_configured)?;
+        match &self.fpga_version {
+            Some(version) => write!(f, "  FPGA Version: {version}"),
+            None => write!(f, "  FPGA Version: N/A"),
+        }
+    }
+}
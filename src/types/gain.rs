@@ -1,5 +1,6 @@
 use std::ffi::CStr;
 
+use enum_map::Enum;
 use strum::FromRepr;
 
 use crate::{sys::*, Error, Result};
@@ -32,6 +33,60 @@ impl TryFrom<bladerf_gain_mode> for GainMode {
     }
 }
 
+/// Named gain stages, see [`crate::BladeRF::get_gain_stages`]
+///
+/// Which stages exist depends on the board and direction.
+#[derive(Copy, Clone, Debug, Enum, PartialEq, Eq)]
+pub enum GainStage {
+    /// bladeRF1 RX low noise amplifier
+    Lna,
+    /// bladeRF1 RX variable gain amplifier 1
+    RxVga1,
+    /// bladeRF1 RX variable gain amplifier 2
+    RxVga2,
+    /// bladeRF1 TX variable gain amplifier 1
+    TxVga1,
+    /// bladeRF1 TX variable gain amplifier 2
+    TxVga2,
+    /// bladeRF2 RX full gain table index
+    Full,
+    /// bladeRF2 RX digital gain
+    Digital,
+    /// bladeRF2 TX digital step attenuator
+    Dsa,
+}
+
+impl GainStage {
+    /// Stage name as used by libbladerf
+    pub fn name(&self) -> &'static str {
+        match self {
+            GainStage::Lna => "lna",
+            GainStage::RxVga1 => "rxvga1",
+            GainStage::RxVga2 => "rxvga2",
+            GainStage::TxVga1 => "txvga1",
+            GainStage::TxVga2 => "txvga2",
+            GainStage::Full => "full",
+            GainStage::Digital => "digital",
+            GainStage::Dsa => "dsa",
+        }
+    }
+
+    /// Look up a stage by its libbladerf name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "lna" => GainStage::Lna,
+            "rxvga1" => GainStage::RxVga1,
+            "rxvga2" => GainStage::RxVga2,
+            "txvga1" => GainStage::TxVga1,
+            "txvga2" => GainStage::TxVga2,
+            "full" => GainStage::Full,
+            "digital" => GainStage::Digital,
+            "dsa" => GainStage::Dsa,
+            _ => return None,
+        })
+    }
+}
+
 /// Mapping between C string description of gain modes and `GainMode`
 pub struct GainModeInfo {
     pub name: &'static str,
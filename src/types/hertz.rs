@@ -0,0 +1,64 @@
+/// A frequency in hertz
+///
+/// Frequency setters accept anything that converts into `Hertz`, so plain `u64` values in Hz
+/// keep working, while the unit constructors make the scale explicit at the call site:
+/// `Hertz::mhz(915.0)` rather than `915_000_000`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Hertz(pub u64);
+
+impl Hertz {
+    pub const fn hz(hz: u64) -> Self {
+        Self(hz)
+    }
+
+    /// Frequency in kHz, rounded to the nearest Hz. Negative values saturate to 0.
+    pub fn khz(khz: f64) -> Self {
+        Self((khz * 1e3).round() as u64)
+    }
+
+    /// Frequency in MHz, rounded to the nearest Hz. Negative values saturate to 0.
+    pub fn mhz(mhz: f64) -> Self {
+        Self((mhz * 1e6).round() as u64)
+    }
+
+    /// Frequency in GHz, rounded to the nearest Hz. Negative values saturate to 0.
+    pub fn ghz(ghz: f64) -> Self {
+        Self((ghz * 1e9).round() as u64)
+    }
+
+    pub const fn as_hz(&self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for Hertz {
+    fn from(hz: u64) -> Self {
+        Self(hz)
+    }
+}
+
+impl From<Hertz> for u64 {
+    fn from(hz: Hertz) -> Self {
+        hz.0
+    }
+}
+
+impl std::fmt::Display for Hertz {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{} Hz", self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hertz_units() {
+        assert_eq!(Hertz::khz(12.5), Hertz(12_500));
+        assert_eq!(Hertz::mhz(915.0), Hertz(915_000_000));
+        assert_eq!(Hertz::ghz(2.4), Hertz(2_400_000_000));
+        assert_eq!(Hertz::mhz(-1.0), Hertz(0));
+        assert_eq!(Hertz::from(433_920_000u64), Hertz::mhz(433.92));
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    ffi::{c_char, CString},
+    path::Path,
+    ptr::NonNull,
+};
+
+use strum::FromRepr;
+
+use crate::{sys::*, BladeRF, Error, FpgaSize, Result, Version};
+
+/// Contents of an [`Image`]
+#[derive(Copy, Clone, Debug, FromRepr, PartialEq, Eq)]
+#[repr(i32)]
+pub enum ImageType {
+    /// Raw flash contents, e.g. a backup of a flash region
+    Raw = bladerf_image_type_BLADERF_IMAGE_TYPE_RAW,
+    Firmware = bladerf_image_type_BLADERF_IMAGE_TYPE_FIRMWARE,
+    Fpga40Kle = bladerf_image_type_BLADERF_IMAGE_TYPE_FPGA_40KLE,
+    Fpga115Kle = bladerf_image_type_BLADERF_IMAGE_TYPE_FPGA_115KLE,
+    FpgaA4 = bladerf_image_type_BLADERF_IMAGE_TYPE_FPGA_A4,
+    FpgaA5 = bladerf_image_type_BLADERF_IMAGE_TYPE_FPGA_A5,
+    FpgaA9 = bladerf_image_type_BLADERF_IMAGE_TYPE_FPGA_A9,
+    /// Board calibration data (FPGA size and VCTCXO trim)
+    Calibration = bladerf_image_type_BLADERF_IMAGE_TYPE_CALIBRATION,
+    RxDcCal = bladerf_image_type_BLADERF_IMAGE_TYPE_RX_DC_CAL,
+    TxDcCal = bladerf_image_type_BLADERF_IMAGE_TYPE_TX_DC_CAL,
+    RxIqCal = bladerf_image_type_BLADERF_IMAGE_TYPE_RX_IQ_CAL,
+    TxIqCal = bladerf_image_type_BLADERF_IMAGE_TYPE_TX_IQ_CAL,
+}
+
+impl TryFrom<bladerf_image_type> for ImageType {
+    type Error = Error;
+
+    fn try_from(value: bladerf_image_type) -> Result<Self> {
+        Self::from_repr(value)
+            .ok_or_else(|| Error::msg(format!("Invalid ImageType value: {value}")))
+    }
+}
+
+/// Address placeholder for images that are not tied to a location in flash
+const NO_ADDRESS: u32 = 0xffff_ffff;
+
+/// A libbladerf image: a blob of flash data with a header recording its type, flash address,
+/// source device serial, creation time and a checksum.
+///
+/// Images are the file format libbladerf uses to back up and restore flash contents and
+/// calibration data. The magic and checksum are filled in by [`Image::write_to_file`] and checked
+/// by [`Image::read_from_file`].
+pub struct Image {
+    image: NonNull<bladerf_image>,
+}
+
+// SAFETY: the image is owned exclusively by this handle and holds no thread-bound state
+unsafe impl Send for Image {}
+
+impl Image {
+    /// Allocate a zeroed image of `length` bytes to be stored at `address` in the device's flash.
+    ///
+    /// `address` must be aligned to a flash page of the device.
+    pub fn new(device: &BladeRF, image_type: ImageType, address: u32, length: u32) -> Result<Self> {
+        let image = unsafe {
+            bladerf_alloc_image(
+                device.device_ptr(),
+                image_type as bladerf_image_type,
+                address,
+                length,
+            )
+        };
+        Self::from_ptr(image)
+    }
+
+    /// Allocate a calibration image holding the FPGA size and VCTCXO trim of a board
+    pub fn new_calibration(
+        device: &BladeRF,
+        fpga_size: FpgaSize,
+        vctcxo_trim: u16,
+    ) -> Result<Self> {
+        let image = unsafe {
+            bladerf_alloc_cal_image(
+                device.device_ptr(),
+                fpga_size as bladerf_fpga_size,
+                vctcxo_trim,
+            )
+        };
+        Self::from_ptr(image)
+    }
+
+    /// Read an image file, failing with [`Error::CHECKSUM`] if its contents do not match the
+    /// checksum in its header.
+    pub fn read_from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path_cstring(path.as_ref())?;
+        // An empty image without a device, which libbladerf fills in from the file
+        let image = unsafe {
+            bladerf_alloc_image(
+                std::ptr::null_mut(),
+                bladerf_image_type_BLADERF_IMAGE_TYPE_INVALID,
+                NO_ADDRESS,
+                0,
+            )
+        };
+        let image = Self::from_ptr(image)?;
+
+        let res = unsafe { bladerf_image_read(image.image.as_ptr(), path.as_ptr()) };
+        if res < 0 {
+            return Err(Error::from_bladerf_code(res as isize));
+        }
+        Ok(image)
+    }
+
+    /// Write the image to a file, stamping it with the serial number of `device`, the current
+    /// time and a checksum of the data.
+    pub fn write_to_file(&mut self, device: &BladeRF, path: impl AsRef<Path>) -> Result<()> {
+        let path = path_cstring(path.as_ref())?;
+        let res =
+            unsafe { bladerf_image_write(device.device_ptr(), self.image.as_ptr(), path.as_ptr()) };
+        if res < 0 {
+            return Err(Error::from_bladerf_code(res as isize));
+        }
+        Ok(())
+    }
+
+    fn from_ptr(image: *mut bladerf_image) -> Result<Self> {
+        NonNull::new(image)
+            .map(|image| Self { image })
+            .ok_or_else(|| Error::msg("Failed to allocate bladerf image"))
+    }
+
+    fn raw(&self) -> &bladerf_image {
+        // SAFETY: allocated by libbladerf and owned by self until Drop
+        unsafe { self.image.as_ref() }
+    }
+
+    pub fn image_type(&self) -> Result<ImageType> {
+        ImageType::try_from(self.raw().type_)
+    }
+
+    /// Flash address the image belongs at, or `None` if it is not tied to one
+    pub fn address(&self) -> Option<u32> {
+        Some(self.raw().address).filter(|&address| address != NO_ADDRESS)
+    }
+
+    /// Version of the image format
+    pub fn version(&self) -> Version {
+        let version = &self.raw().version;
+        Version {
+            major: version.major,
+            minor: version.minor,
+            patch: version.patch,
+            describe: None,
+        }
+    }
+
+    /// Creation time in seconds since the Unix epoch, set when the image is written
+    pub fn timestamp(&self) -> u64 {
+        self.raw().timestamp
+    }
+
+    /// Serial number of the device the image was written from
+    pub fn serial(&self) -> String {
+        c_chars_to_string(&self.raw().serial)
+    }
+
+    /// SHA-256 checksum of the image, set when it is written
+    pub fn checksum(&self) -> &[u8] {
+        &self.raw().checksum
+    }
+
+    pub fn data(&self) -> &[u8] {
+        let image = self.raw();
+        if image.data.is_null() {
+            return &[];
+        }
+        // SAFETY: libbladerf allocates `length` bytes of data with the image
+        unsafe { std::slice::from_raw_parts(image.data, image.length as usize) }
+    }
+
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        // SAFETY: as for data, and self is borrowed exclusively
+        let image = unsafe { self.image.as_mut() };
+        if image.data.is_null() {
+            return &mut [];
+        }
+        unsafe { std::slice::from_raw_parts_mut(image.data, image.length as usize) }
+    }
+}
+
+impl Drop for Image {
+    fn drop(&mut self) {
+        unsafe { bladerf_free_image(self.image.as_ptr()) };
+    }
+}
+
+impl std::fmt::Debug for Image {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Image")
+            .field("image_type", &self.image_type())
+            .field("address", &self.address())
+            .field("length", &self.data().len())
+            .field("serial", &self.serial())
+            .field("timestamp", &self.timestamp())
+            .finish()
+    }
+}
+
+fn path_cstring(path: &Path) -> Result<CString> {
+    CString::new(path.as_os_str().as_encoded_bytes())
+        .map_err(|e| Error::msg(format!("Invalid path for cstring: {e:?}")))
+}
+
+/// Read a fixed size, possibly unterminated, C string field
+fn c_chars_to_string(chars: &[c_char]) -> String {
+    let bytes: Vec<u8> = chars
+        .iter()
+        .map(|&c| c as u8)
+        .take_while(|&c| c != 0)
+        .collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
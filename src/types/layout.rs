@@ -1,7 +1,7 @@
 use enum_map::Enum;
 use strum::FromRepr;
 
-use crate::{sys::*, Error, Result};
+use crate::{sys::*, Channel, Direction, Error, Result};
 
 #[derive(Copy, Clone, Debug, Enum, FromRepr, PartialEq, Eq)]
 #[repr(u32)]
@@ -25,6 +25,22 @@ impl ChannelLayout {
     pub fn is_mimo(&self) -> bool {
         matches!(self, ChannelLayout::RxMIMO | ChannelLayout::TxMIMO)
     }
+    pub fn direction(&self) -> Direction {
+        if self.is_rx() {
+            Direction::RX
+        } else {
+            Direction::TX
+        }
+    }
+    /// Channels carried by a stream with this layout, in interleave order
+    pub fn channels(&self) -> &'static [Channel] {
+        match self {
+            ChannelLayout::RxSISO => &[Channel::Rx0],
+            ChannelLayout::RxMIMO => &[Channel::Rx0, Channel::Rx1],
+            ChannelLayout::TxSISO => &[Channel::Tx0],
+            ChannelLayout::TxMIMO => &[Channel::Tx0, Channel::Tx1],
+        }
+    }
 }
 
 impl TryFrom<bladerf_channel_layout> for ChannelLayout {
@@ -21,3 +21,30 @@ impl TryFrom<bladerf_log_level> for LogLevel {
         Self::from_repr(level).ok_or_else(|| format!("Invalid bladerf log level: {level}").into())
     }
 }
+
+/// Map a `log` crate filter onto the closest libbladerf verbosity, see
+/// [`crate::set_log_level_from_logger`]
+impl From<log::LevelFilter> for LogLevel {
+    fn from(filter: log::LevelFilter) -> Self {
+        match filter {
+            log::LevelFilter::Off => LogLevel::Silent,
+            log::LevelFilter::Error => LogLevel::Error,
+            log::LevelFilter::Warn => LogLevel::Warning,
+            log::LevelFilter::Info => LogLevel::Info,
+            log::LevelFilter::Debug => LogLevel::Debug,
+            log::LevelFilter::Trace => LogLevel::Verbose,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_level_from_filter() {
+        assert_eq!(LogLevel::from(log::LevelFilter::Off), LogLevel::Silent);
+        assert_eq!(LogLevel::from(log::LevelFilter::Warn), LogLevel::Warning);
+        assert_eq!(LogLevel::from(log::LevelFilter::Trace), LogLevel::Verbose);
+    }
+}
@@ -1,24 +1,60 @@
 use std::ffi::CStr;
 
-use crate::{sys::*, Error, Result};
+use crate::{sys::*, BoardKind, Error, Result};
 use strum::FromRepr;
 
 /// Loopback configuration
+///
+/// Every libbladerf loopback mode, see [`Loopback::supported_on`] for the modes each board
+/// implements.
 #[derive(Copy, Clone, Debug, FromRepr, PartialEq, Eq)]
 #[repr(u32)]
 pub enum Loopback {
+    /// Normal operation, all boards
     None = bladerf_loopback_BLADERF_LB_NONE,
+    /// bladeRF1: TX mixer output to the RX LNA1 input
     RfLna1 = bladerf_loopback_BLADERF_LB_RF_LNA1,
+    /// bladeRF1: TX mixer output to the RX LNA2 input
     RfLna2 = bladerf_loopback_BLADERF_LB_RF_LNA2,
+    /// bladeRF1: TX mixer output to the RX LNA3 input
     RfLna3 = bladerf_loopback_BLADERF_LB_RF_LNA3,
+    /// All boards: TX samples are looped back to RX in the FX3 firmware
     Firmware = bladerf_loopback_BLADERF_LB_FIRMWARE,
+    /// bladeRF2: digital loopback inside the RFIC's built-in self test
     RficBist = bladerf_loopback_BLADERF_LB_RFIC_BIST,
+    /// bladeRF1: TX LPF output to the RX LPF input
     BbTxlpfRxlpf = bladerf_loopback_BLADERF_LB_BB_TXLPF_RXLPF,
+    /// bladeRF1: TX LPF output to the RX VGA2 input
     BbTxlpfRxvga2 = bladerf_loopback_BLADERF_LB_BB_TXLPF_RXVGA2,
+    /// bladeRF1: TX VGA1 output to the RX LPF input
     BbTxvga1Rxlpf = bladerf_loopback_BLADERF_LB_BB_TXVGA1_RXLPF,
+    /// bladeRF1: TX VGA1 output to the RX VGA2 input
     BbTxvga1Rxvga2 = bladerf_loopback_BLADERF_LB_BB_TXVGA1_RXVGA2,
 }
 
+impl Loopback {
+    /// Loopback modes libbladerf implements for `board`, in the order
+    /// [`crate::BladeRF::get_loopback_modes`] reports them.
+    ///
+    /// The FPGA image can restrict this further, the device query stays authoritative.
+    pub fn supported_on(board: BoardKind) -> &'static [Loopback] {
+        match board {
+            BoardKind::BladeRf1 => &[
+                Loopback::Firmware,
+                Loopback::BbTxlpfRxvga2,
+                Loopback::BbTxlpfRxlpf,
+                Loopback::BbTxvga1Rxvga2,
+                Loopback::BbTxvga1Rxlpf,
+                Loopback::RfLna1,
+                Loopback::RfLna2,
+                Loopback::RfLna3,
+                Loopback::None,
+            ],
+            BoardKind::BladeRf2 => &[Loopback::None, Loopback::Firmware, Loopback::RficBist],
+        }
+    }
+}
+
 impl TryFrom<bladerf_loopback> for Loopback {
     type Error = Error;
 
@@ -45,3 +81,25 @@ impl From<bladerf_loopback_modes> for LoopbackModeInfo {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loopback_modes_per_board() {
+        for board in [BoardKind::BladeRf1, BoardKind::BladeRf2] {
+            let modes = Loopback::supported_on(board);
+            assert!(modes.contains(&Loopback::None));
+            assert!(modes.contains(&Loopback::Firmware));
+        }
+        assert!(!Loopback::supported_on(BoardKind::BladeRf1).contains(&Loopback::RficBist));
+        assert!(!Loopback::supported_on(BoardKind::BladeRf2).contains(&Loopback::RfLna1));
+
+        assert_eq!(
+            Loopback::try_from(bladerf_loopback_BLADERF_LB_RFIC_BIST),
+            Ok(Loopback::RficBist)
+        );
+        assert!(Loopback::try_from(u32::MAX).is_err());
+    }
+}
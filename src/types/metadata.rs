@@ -5,7 +5,8 @@ use crate::sys::*;
 pub struct Metadata {
     pub timestamp: u64,
     pub flags: u32,
-    // Add other fields as necessary
+    /// `BLADERF_META_STATUS_*` flags reported by the last transfer
+    pub status: u32,
 }
 
 impl Default for Metadata {
@@ -19,8 +20,58 @@ impl Metadata {
         Self {
             timestamp: 0,
             flags: 0,
+            status: 0,
+        }
+    }
+
+    /// Metadata targeting the given device timestamp
+    pub fn with_timestamp(timestamp: u64) -> Self {
+        Self {
+            timestamp,
+            flags: 0,
+            status: 0,
         }
     }
+
+    /// Mark the first buffer of a TX burst
+    pub fn burst_start(mut self) -> Self {
+        self.flags |= BLADERF_META_FLAG_TX_BURST_START;
+        self
+    }
+
+    /// Mark the last buffer of a TX burst, the burst may start and end in the same buffer
+    pub fn burst_end(mut self) -> Self {
+        self.flags |= BLADERF_META_FLAG_TX_BURST_END;
+        self
+    }
+
+    /// Transmit the burst as soon as possible, ignoring the timestamp
+    pub fn tx_now(mut self) -> Self {
+        self.flags |= BLADERF_META_FLAG_TX_NOW;
+        self
+    }
+
+    /// Pad with zeros up to the timestamp within a burst instead of ending it
+    pub fn update_timestamp(mut self) -> Self {
+        self.flags |= BLADERF_META_FLAG_TX_UPDATE_TIMESTAMP;
+        self
+    }
+
+    /// Receive the next available samples, ignoring the timestamp
+    pub fn rx_now(mut self) -> Self {
+        self.flags |= BLADERF_META_FLAG_RX_NOW;
+        self
+    }
+
+    /// The RX transfer reported an overrun: samples were dropped before this buffer
+    pub fn overrun(&self) -> bool {
+        self.status & BLADERF_META_STATUS_OVERRUN != 0
+    }
+
+    /// The TX transfer reported an underrun: the device ran out of samples to transmit
+    pub fn underrun(&self) -> bool {
+        self.status & BLADERF_META_STATUS_UNDERRUN != 0
+    }
 }
 
 impl From<&bladerf_metadata> for Metadata {
@@ -28,6 +79,7 @@ impl From<&bladerf_metadata> for Metadata {
         Self {
             timestamp: meta.timestamp,
             flags: meta.flags,
+            status: meta.status,
         }
     }
 }
@@ -37,9 +89,42 @@ impl From<&Metadata> for bladerf_metadata {
         bladerf_metadata {
             timestamp: val.timestamp,
             flags: val.flags,
-            status: 0,
+            status: val.status,
             actual_count: 0,
             reserved: [0u8; 32],
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metadata_builder_flags() {
+        let meta = Metadata::with_timestamp(1234).burst_start().burst_end();
+        assert_eq!(meta.timestamp, 1234);
+        assert_eq!(
+            meta.flags,
+            BLADERF_META_FLAG_TX_BURST_START | BLADERF_META_FLAG_TX_BURST_END
+        );
+
+        let meta = Metadata::new().burst_start().tx_now();
+        assert_eq!(
+            meta.flags,
+            BLADERF_META_FLAG_TX_BURST_START | BLADERF_META_FLAG_TX_NOW
+        );
+        assert!(!meta.overrun() && !meta.underrun());
+
+        let raw = bladerf_metadata {
+            timestamp: 0,
+            flags: 0,
+            status: BLADERF_META_STATUS_OVERRUN,
+            actual_count: 0,
+            reserved: [0u8; 32],
+        };
+        let meta = Metadata::from(&raw);
+        assert!(meta.overrun());
+        assert!(!meta.underrun());
+    }
+}
@@ -0,0 +1,73 @@
+use crate::SampleFormat;
+
+/// Split a MIMO buffer into its two channels.
+///
+/// With a MIMO [`crate::ChannelLayout`], libbladerf interleaves the channels sample by sample:
+/// `[ch0[0], ch1[0], ch0[1], ch1[1], ...]`, so `src` holds twice as many samples as each channel.
+///
+/// # Panics
+/// If `ch0` and `ch1` are not each half the length of `src`.
+pub fn deinterleave_mimo<T: SampleFormat + Copy>(src: &[T], ch0: &mut [T], ch1: &mut [T]) {
+    assert_eq!(
+        src.len(),
+        ch0.len() * 2,
+        "ch0 must be half the length of src"
+    );
+    assert_eq!(
+        src.len(),
+        ch1.len() * 2,
+        "ch1 must be half the length of src"
+    );
+
+    for ((pair, a), b) in src.chunks_exact(2).zip(ch0).zip(ch1) {
+        *a = pair[0];
+        *b = pair[1];
+    }
+}
+
+/// Combine two channels into a MIMO buffer, see [`deinterleave_mimo`] for the layout.
+///
+/// # Panics
+/// If `ch0` and `ch1` are not each half the length of `dst`.
+pub fn interleave_mimo<T: SampleFormat + Copy>(ch0: &[T], ch1: &[T], dst: &mut [T]) {
+    assert_eq!(
+        dst.len(),
+        ch0.len() * 2,
+        "ch0 must be half the length of dst"
+    );
+    assert_eq!(
+        dst.len(),
+        ch1.len() * 2,
+        "ch1 must be half the length of dst"
+    );
+
+    for ((pair, a), b) in dst.chunks_exact_mut(2).zip(ch0).zip(ch1) {
+        pair[0] = *a;
+        pair[1] = *b;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_complex::Complex;
+
+    use super::*;
+
+    #[test]
+    fn mimo_interleave_layout() {
+        // Channel in the real part, sample index in the imaginary part
+        let buffer: Vec<Complex<i16>> = (0..8).map(|i| Complex::new(i % 2, i / 2)).collect();
+
+        let mut ch0 = [Complex::default(); 4];
+        let mut ch1 = [Complex::default(); 4];
+        deinterleave_mimo(&buffer, &mut ch0, &mut ch1);
+        for i in 0..4 {
+            assert_eq!(ch0[i], Complex::new(0, i as i16));
+            assert_eq!(ch1[i], Complex::new(1, i as i16));
+        }
+
+        let mut round_trip = vec![Complex::default(); 8];
+        interleave_mimo(&ch0, &ch1, &mut round_trip);
+        assert_eq!(round_trip, buffer);
+    }
+}
@@ -63,3 +63,51 @@ pub use trigger::*;
 
 mod layout;
 pub use layout::*;
+
+mod stream_config;
+pub use stream_config::*;
+
+mod device_speed;
+pub use device_speed::*;
+
+mod full_device_info;
+pub use full_device_info::*;
+
+mod expansion_module;
+pub use expansion_module::*;
+
+mod fpga_size;
+pub use fpga_size::*;
+
+mod fpga_source;
+pub use fpga_source::*;
+
+mod hertz;
+pub use hertz::*;
+
+mod vctcxo_tamer_mode;
+pub use vctcxo_tamer_mode::*;
+
+mod dc_cal_module;
+pub use dc_cal_module::*;
+
+mod pmic_register;
+pub use pmic_register::*;
+
+mod image;
+pub use image::*;
+
+mod mimo;
+pub use mimo::*;
+
+mod board_kind;
+pub use board_kind::*;
+
+mod self_test;
+pub use self_test::*;
+
+mod stream_stats;
+pub use stream_stats::*;
+
+mod clock_select;
+pub use clock_select::*;
@@ -1,3 +1,5 @@
+use crate::{BladeRF, Channel, Error, Range, Result};
+
 /// BladeRF module config object
 #[derive(Clone, Debug)]
 pub struct ModuleConfig {
@@ -7,3 +9,65 @@ pub struct ModuleConfig {
     /// Set overall system gain
     pub gain: i32,
 }
+
+impl ModuleConfig {
+    /// Check the frequency, sample rate and bandwidth against the ranges `device` reports for
+    /// `channel`, failing with an [`Error::Msg`] naming the valid range of the first one out of
+    /// bounds.
+    ///
+    /// The gain range depends on the frequency the channel is tuned to, so the gain is not
+    /// checked here. [`BladeRF::configure_module_checked`] checks it once the frequency has been
+    /// applied.
+    ///
+    /// Values between steps of a range pass, the device rounds them when they are applied. Use
+    /// [`BladeRF::configure_module_checked`] to learn the values it settled on.
+    pub fn validate(&self, device: &BladeRF, channel: Channel) -> Result<()> {
+        check_bounds(
+            "frequency",
+            channel,
+            self.frequency as f64,
+            &device.get_frequency_range(channel)?,
+        )?;
+        check_bounds(
+            "sample rate",
+            channel,
+            self.sample_rate as f64,
+            &device.get_sample_rate_range(channel)?,
+        )?;
+        check_bounds(
+            "bandwidth",
+            channel,
+            self.bandwidth as f64,
+            &device.get_bandwidth_range(channel)?,
+        )
+    }
+
+    /// Check the gain against the range of `channel` at the frequency it is currently tuned to
+    pub(crate) fn validate_gain(&self, device: &BladeRF, channel: Channel) -> Result<()> {
+        check_bounds(
+            "gain",
+            channel,
+            self.gain as f64,
+            &device.get_gain_range(channel)?,
+        )
+    }
+}
+
+/// Values a [`ModuleConfig`] resulted in once applied, see
+/// [`BladeRF::configure_module_checked`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AppliedModuleConfig {
+    pub frequency: u64,
+    pub sample_rate: u32,
+    pub bandwidth: u32,
+    pub gain: i32,
+}
+
+fn check_bounds(param: &str, channel: Channel, value: f64, range: &Range) -> Result<()> {
+    if !range.contains_value(value) {
+        return Err(Error::msg(format!(
+            "{param} {value} is not valid on {channel}, valid range is {range}"
+        )));
+    }
+    Ok(())
+}
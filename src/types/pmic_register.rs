@@ -0,0 +1,54 @@
+use strum::FromRepr;
+
+use crate::{sys::*, Error, Result};
+
+/// Registers of the bladeRF2 INA219 power monitor, see [`crate::BladeRF::get_pmic_register`]
+#[derive(Copy, Clone, Debug, FromRepr, PartialEq, Eq)]
+#[repr(u32)]
+pub enum PmicRegister {
+    /// Raw configuration register
+    Configuration = bladerf_pmic_register_BLADERF_PMIC_CONFIGURATION,
+    /// Shunt voltage in volts
+    VoltageShunt = bladerf_pmic_register_BLADERF_PMIC_VOLTAGE_SHUNT,
+    /// Bus voltage in volts
+    VoltageBus = bladerf_pmic_register_BLADERF_PMIC_VOLTAGE_BUS,
+    /// Load power in watts
+    Power = bladerf_pmic_register_BLADERF_PMIC_POWER,
+    /// Load current in amps
+    Current = bladerf_pmic_register_BLADERF_PMIC_CURRENT,
+    /// Raw calibration register
+    Calibration = bladerf_pmic_register_BLADERF_PMIC_CALIBRATION,
+}
+
+impl PmicRegister {
+    /// True for the raw 16-bit registers, false for measurements converted to physical units
+    pub fn is_raw(&self) -> bool {
+        matches!(
+            self,
+            PmicRegister::Configuration | PmicRegister::Calibration
+        )
+    }
+}
+
+impl TryFrom<bladerf_pmic_register> for PmicRegister {
+    type Error = Error;
+
+    fn try_from(value: bladerf_pmic_register) -> Result<Self> {
+        Self::from_repr(value)
+            .ok_or_else(|| Error::msg(format!("Invalid PmicRegister value: {value}")))
+    }
+}
+
+/// One reading of every bladeRF2 power monitor measurement, see
+/// [`crate::BladeRF::get_power_monitoring`]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PowerMonitor {
+    /// Bus voltage in volts
+    pub voltage_bus: f32,
+    /// Shunt voltage in volts
+    pub voltage_shunt: f32,
+    /// Load power in watts
+    pub power: f32,
+    /// Load current in amps
+    pub current: f32,
+}
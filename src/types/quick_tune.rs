@@ -1,4 +1,24 @@
-#[derive(Clone, Debug)]
+use crate::{Error, Result};
+
+/// Tuning parameters captured by [`crate::BladeRF::get_quick_tune`], used to retune to the same
+/// frequency without rerunning the tuning algorithm.
+///
+/// Mirrors libbladerf's `bladerf_quick_tune`, a union of a bladeRF1 and a bladeRF2 layout. The
+/// fields are the bladeRF1 layout, while the bladeRF2 fields are available through
+/// [`QuickTune::nios_profile`] and friends.
+///
+/// On the bladeRF2 a quick tune refers to a fast lock profile, not a set of tuning words. Each
+/// call to `get_quick_tune` stores the current tuning in the next of 256 profiles kept in the
+/// FPGA, overwriting the oldest once all are used, and libbladerf loads it into one of the 8
+/// fast lock slots of the RFIC when it is scheduled. Only the 256 most recent quick tunes of a
+/// direction are therefore valid.
+///
+/// Quick tunes can be persisted with [`QuickTune::to_bytes`] or, with the `serde` feature, any
+/// serde format, and replayed with [`crate::BladeRF::schedule_retune`] in a later run. On the
+/// bladeRF2 this only works while the profiles are still stored in the FPGA, i.e. not across
+/// power cycles or FPGA reloads.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct QuickTune {
     pub freqsel: u8,
@@ -6,4 +26,107 @@ pub struct QuickTune {
     pub nint: u16,
     pub nfrac: u32,
     pub flags: u8,
+    /// bladeRF1 XB-200 filter bank GPIO state
+    pub xb_gpio: u8,
+}
+
+impl QuickTune {
+    /// Length of the encoding produced by [`QuickTune::to_bytes`]
+    pub const ENCODED_LEN: usize = 10;
+
+    /// Encode the fields in a fixed little-endian layout, independent of the host
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LEN] {
+        let mut bytes = [0u8; Self::ENCODED_LEN];
+        bytes[0] = self.freqsel;
+        bytes[1] = self.vcocap;
+        bytes[2..4].copy_from_slice(&self.nint.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.nfrac.to_le_bytes());
+        bytes[8] = self.flags;
+        bytes[9] = self.xb_gpio;
+        bytes
+    }
+
+    /// Decode a quick tune written by [`QuickTune::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let bytes: &[u8; Self::ENCODED_LEN] = bytes.try_into().map_err(|_| {
+            Error::msg(format!(
+                "Invalid quick tune encoding length: {} (expected {})",
+                bytes.len(),
+                Self::ENCODED_LEN
+            ))
+        })?;
+
+        Ok(Self {
+            freqsel: bytes[0],
+            vcocap: bytes[1],
+            nint: u16::from_le_bytes([bytes[2], bytes[3]]),
+            nfrac: u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+            flags: bytes[8],
+            xb_gpio: bytes[9],
+        })
+    }
+
+    /// bladeRF2 profile index in the FPGA profile store
+    pub fn nios_profile(&self) -> u16 {
+        u16::from_ne_bytes([self.freqsel, self.vcocap])
+    }
+
+    /// bladeRF2 RFIC fast lock slot the profile was last loaded into
+    pub fn rffe_profile(&self) -> u8 {
+        self.nint.to_ne_bytes()[0]
+    }
+
+    /// bladeRF2 RFFE port configuration of the profile
+    pub fn port(&self) -> u8 {
+        self.nint.to_ne_bytes()[1]
+    }
+
+    /// bladeRF2 SPDT switch configuration of the profile
+    pub fn spdt(&self) -> u8 {
+        self.nfrac.to_ne_bytes()[0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quick_tune_bladerf2_view() {
+        assert_eq!(std::mem::size_of::<QuickTune>(), 12);
+
+        // Lay out the bladeRF2 struct { u16 nios_profile; u8 rffe_profile; u8 port; u8 spdt; }
+        let mut bytes = [0u8; 12];
+        bytes[..2].copy_from_slice(&300u16.to_ne_bytes());
+        bytes[2..5].copy_from_slice(&[5, 0x12, 0x34]);
+        let quick_tune = QuickTune {
+            freqsel: bytes[0],
+            vcocap: bytes[1],
+            nint: u16::from_ne_bytes([bytes[2], bytes[3]]),
+            nfrac: u32::from_ne_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+            ..Default::default()
+        };
+        assert_eq!(quick_tune.nios_profile(), 300);
+        assert_eq!(quick_tune.rffe_profile(), 5);
+        assert_eq!(quick_tune.port(), 0x12);
+        assert_eq!(quick_tune.spdt(), 0x34);
+    }
+
+    #[test]
+    fn quick_tune_bytes_round_trip() {
+        let quick_tune = QuickTune {
+            freqsel: 0x2c,
+            vcocap: 17,
+            nint: 0x1234,
+            nfrac: 0x0056_789a,
+            flags: 1,
+            xb_gpio: 0x30,
+        };
+        let bytes = quick_tune.to_bytes();
+        assert_eq!(bytes[2..4], [0x34, 0x12]);
+        assert_eq!(QuickTune::from_bytes(&bytes), Ok(quick_tune));
+
+        assert!(QuickTune::from_bytes(&bytes[..9]).is_err());
+        assert!(QuickTune::from_bytes(&[0u8; 11]).is_err());
+    }
 }
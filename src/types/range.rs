@@ -1,7 +1,7 @@
 use crate::sys::*;
 
 /// Range struct to represent `bladerf_range`
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Range {
     pub min: f64,
     pub max: f64,
@@ -9,12 +9,75 @@ pub struct Range {
 }
 
 impl Range {
+    /// True if `value` is within the bounds of the range, regardless of its step
+    pub fn contains_value(&self, value: f64) -> bool {
+        (self.min..=self.max).contains(&value)
+    }
+
+    /// True if `query` is within the bounds and on a step of the range
     pub fn contains(&self, query: impl Into<u64>) -> bool {
-        let steps = (query.into() as f64 - self.min) / self.step;
-        steps % 1.0 < 1e-8
+        let query = query.into() as f64;
+        if !self.contains_value(query) {
+            return false;
+        }
+        if self.step <= 0.0 {
+            return true;
+        }
+        let steps = (query - self.min) / self.step;
+        (steps - steps.round()).abs() < 1e-8
+    }
+
+    /// Every valid value of the range, from the minimum up to the maximum.
+    ///
+    /// A range without a step yields only the minimum.
+    pub fn iter(&self) -> impl Iterator<Item = f64> {
+        let (min, max, step) = (self.min, self.max, self.step);
+        let count = if step > 0.0 {
+            ((max - min) / step + 1e-8).floor() as u64 + 1
+        } else {
+            1
+        };
+        (0..count).map(move |i| min + i as f64 * step)
+    }
+
+    /// Limit `value` to the bounds of the range
+    pub fn clamp(&self, value: f64) -> f64 {
+        value.clamp(self.min, self.max)
+    }
+
+    /// The valid value nearest to `value`: clamped to the bounds, then rounded to the nearest
+    /// step. Never returns a value past the maximum, even when it is not on a step.
+    pub fn snap(&self, value: f64) -> f64 {
+        let value = self.clamp(value);
+        if self.step <= 0.0 {
+            return value;
+        }
+        let snapped = self.min + ((value - self.min) / self.step).round() * self.step;
+        if snapped > self.max {
+            snapped - self.step
+        } else {
+            snapped
+        }
     }
 }
 
+/// Tune points for scanning `range` every `step_hz`, starting at the range minimum.
+///
+/// `step_hz` is rounded up to a multiple of the range's own step so every point lands on the
+/// tuning grid, and no point exceeds the range maximum. A step larger than the range yields only
+/// the minimum.
+pub fn frequency_steps(range: &Range, step_hz: u64) -> impl Iterator<Item = u64> {
+    let grid = if range.step >= 1.0 {
+        range.step as u64
+    } else {
+        1
+    };
+    let step = step_hz.max(1).div_ceil(grid) * grid;
+    let min = range.min.ceil() as u64;
+    let max = range.max.floor() as u64;
+    (min..=max).step_by(step as usize)
+}
+
 impl std::fmt::Display for Range {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!(
@@ -33,3 +96,71 @@ impl From<&bladerf_range> for Range {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_helpers() {
+        let range = Range {
+            min: 10.0,
+            max: 21.0,
+            step: 4.0,
+        };
+        assert!(range.contains(14u32));
+        assert!(!range.contains(15u32));
+        assert!(!range.contains(6u32));
+        assert!(!range.contains(22u32));
+        assert!(range.contains_value(15.0));
+        assert!(range.contains_value(21.0));
+        assert!(!range.contains_value(21.5));
+
+        assert_eq!(range.iter().collect::<Vec<_>>(), [10.0, 14.0, 18.0]);
+        assert_eq!(range.clamp(0.0), 10.0);
+        assert_eq!(range.clamp(30.0), 21.0);
+        assert_eq!(range.snap(15.5), 14.0);
+        assert_eq!(range.snap(16.5), 18.0);
+        // 22 is the nearest step but past the maximum
+        assert_eq!(range.snap(20.5), 18.0);
+        assert_eq!(range.snap(-5.0), 10.0);
+    }
+
+    #[test]
+    fn frequency_steps_grid() {
+        let range = Range {
+            min: 70e6,
+            max: 6e9,
+            step: 2.0,
+        };
+        let steps: Vec<u64> = frequency_steps(&range, 1_000_000_000).collect();
+        assert_eq!(
+            steps,
+            [
+                70_000_000,
+                1_070_000_000,
+                2_070_000_000,
+                3_070_000_000,
+                4_070_000_000,
+                5_070_000_000
+            ]
+        );
+
+        // Rounded up onto the 2 Hz grid
+        let steps: Vec<u64> = frequency_steps(&range, 3).take(3).collect();
+        assert_eq!(steps, [70_000_000, 70_000_004, 70_000_008]);
+
+        // Step larger than the range
+        let steps: Vec<u64> = frequency_steps(&range, 10_000_000_000).collect();
+        assert_eq!(steps, [70_000_000]);
+
+        // Inclusive of the maximum when it lands on a step
+        let range = Range {
+            min: 100.0,
+            max: 200.0,
+            step: 1.0,
+        };
+        let steps: Vec<u64> = frequency_steps(&range, 50).collect();
+        assert_eq!(steps, [100, 150, 200]);
+    }
+}
@@ -1,5 +1,11 @@
-use crate::sys::*;
+use std::cmp::Ordering;
 
+use crate::{sys::*, Error, Result};
+
+/// A rate of `integer + num / den`
+///
+/// Comparisons use the value of the rate, so `1 + 1/2` equals `1 + 2/4`.
+#[derive(Copy, Clone, Debug)]
 pub struct RationalRate {
     /// Integer portion
     pub integer: u64,
@@ -9,6 +15,156 @@ pub struct RationalRate {
     pub den: u64,
 }
 
+/// Largest denominator [`RationalRate::from`] an `f64` will produce, well beyond the precision of
+/// the bladeRF clock synthesizers
+const MAX_APPROX_DEN: u64 = 1 << 32;
+
+impl RationalRate {
+    /// Create a rate of `integer + num / den`, failing if `den` is zero
+    pub fn new(integer: u64, num: u64, den: u64) -> Result<Self> {
+        if den == 0 {
+            return Err(Error::msg("Rational rate denominator must be non-zero"));
+        }
+        Ok(Self { integer, num, den })
+    }
+
+    /// Divide `num` and `den` by their greatest common divisor, leaving `integer` untouched.
+    ///
+    /// See [`RationalRate::normalize`] to also carry whole parts of the fraction into `integer`.
+    pub fn reduce(&self) -> Self {
+        let divisor = gcd(self.num, self.den);
+        if divisor == 0 {
+            return *self;
+        }
+        Self {
+            integer: self.integer,
+            num: self.num / divisor,
+            den: self.den / divisor,
+        }
+    }
+
+    /// The rate as a floating point value. Precision is lost for very large rates.
+    pub fn to_f64(&self) -> f64 {
+        if self.den == 0 {
+            return self.integer as f64;
+        }
+        self.integer as f64 + self.num as f64 / self.den as f64
+    }
+
+    /// Reduce the fraction and carry any whole part of `num / den` into `integer`.
+    ///
+    /// A zero numerator normalizes to `0/1`. A zero denominator is treated as no fractional part.
+    pub fn normalize(&self) -> Self {
+        if self.den == 0 || self.num == 0 {
+            return Self {
+                integer: self.integer,
+                num: 0,
+                den: 1,
+            };
+        }
+
+        let integer = self.integer + self.num / self.den;
+        let num = self.num % self.den;
+        let divisor = gcd(num, self.den);
+        Self {
+            integer,
+            num: num / divisor,
+            den: self.den / divisor,
+        }
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl PartialEq for RationalRate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for RationalRate {}
+
+impl PartialOrd for RationalRate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RationalRate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let a = self.normalize();
+        let b = other.normalize();
+        // Both fractions are below 1 after normalizing, so the products fit in a u128
+        a.integer
+            .cmp(&b.integer)
+            .then_with(|| (a.num as u128 * b.den as u128).cmp(&(b.num as u128 * a.den as u128)))
+    }
+}
+
+/// Best rational approximation of a rate, found with continued fractions and limited to
+/// denominators of at most 2^32.
+///
+/// Negative and NaN values give a rate of zero.
+impl From<f64> for RationalRate {
+    fn from(rate: f64) -> Self {
+        if rate.is_nan() || rate <= 0.0 {
+            return Self {
+                integer: 0,
+                num: 0,
+                den: 1,
+            };
+        }
+
+        let integer = rate.trunc();
+        let frac = rate - integer;
+
+        // Convergents h/k of the continued fraction of frac
+        let (mut h_prev, mut h) = (1u64, 0u64);
+        let (mut k_prev, mut k) = (0u64, 1u64);
+        let mut x = frac;
+        while x > 0.0 {
+            let a = (1.0 / x).trunc();
+            if a > MAX_APPROX_DEN as f64 {
+                break;
+            }
+            let a = a as u64;
+            let k_next = match a.checked_mul(k).and_then(|v| v.checked_add(k_prev)) {
+                Some(k_next) if k_next <= MAX_APPROX_DEN => k_next,
+                _ => break,
+            };
+            (h_prev, h) = (h, a * h + h_prev);
+            (k_prev, k) = (k, k_next);
+            x = 1.0 / x - a as f64;
+
+            if (h as f64 / k as f64 - frac).abs() <= f64::EPSILON * rate {
+                break;
+            }
+        }
+
+        Self {
+            integer: integer as u64,
+            num: h,
+            den: k,
+        }
+        .normalize()
+    }
+}
+
+impl From<RationalRate> for bladerf_rational_rate {
+    fn from(rate: RationalRate) -> Self {
+        Self {
+            integer: rate.integer,
+            num: rate.num,
+            den: rate.den,
+        }
+    }
+}
+
 impl From<bladerf_rational_rate> for RationalRate {
     fn from(rate: bladerf_rational_rate) -> Self {
         Self {
@@ -18,3 +174,52 @@ impl From<bladerf_rational_rate> for RationalRate {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate(integer: u64, num: u64, den: u64) -> RationalRate {
+        RationalRate { integer, num, den }
+    }
+
+    #[test]
+    fn rational_rate_normalize() {
+        let normalized = rate(1, 6, 4).normalize();
+        assert_eq!(
+            (normalized.integer, normalized.num, normalized.den),
+            (2, 1, 2)
+        );
+
+        let normalized = rate(5, 0, 7).normalize();
+        assert_eq!(
+            (normalized.integer, normalized.num, normalized.den),
+            (5, 0, 1)
+        );
+    }
+
+    #[test]
+    fn rational_rate_helpers() {
+        assert!(RationalRate::new(1, 1, 0).is_err());
+
+        let reduced = RationalRate::new(3, 6, 4).unwrap().reduce();
+        assert_eq!((reduced.integer, reduced.num, reduced.den), (3, 3, 2));
+        assert_eq!(reduced.to_f64(), 4.5);
+
+        let approx = RationalRate::from(30.72e6 + 1.0 / 3.0);
+        assert_eq!((approx.integer, approx.num, approx.den), (30_720_000, 1, 3));
+        let approx = RationalRate::from(1.0 + 7.0 / 9.0);
+        assert_eq!((approx.integer, approx.num, approx.den), (1, 7, 9));
+        assert_eq!(RationalRate::from(-1.0), rate(0, 0, 1));
+        assert_eq!(RationalRate::from(5.0), rate(5, 0, 1));
+    }
+
+    #[test]
+    fn rational_rate_cmp() {
+        assert_eq!(rate(1, 1, 2), rate(1, 2, 4));
+        assert_eq!(rate(2, 0, 1), rate(1, 3, 3));
+        assert!(rate(1, 1, 3) < rate(1, 1, 2));
+        assert!(rate(2, 0, 1) > rate(1, 999, 1000));
+        assert!(rate(0, u64::MAX - 1, u64::MAX) < rate(0, u64::MAX, u64::MAX));
+    }
+}
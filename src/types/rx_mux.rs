@@ -1,13 +1,17 @@
+use num_complex::Complex;
 use strum::FromRepr;
 
 use crate::{sys::*, Error, Result};
 
+/// Source of the RX samples, see [`crate::BladeRF::set_rx_mux`]
 #[derive(Copy, Clone, Debug, FromRepr, PartialEq, Eq)]
 #[repr(i32)]
 pub enum RxMux {
     Invalid = bladerf_rx_mux_BLADERF_RX_MUX_INVALID,
     Baseband = bladerf_rx_mux_BLADERF_RX_MUX_BASEBAND,
     Counter12bit = bladerf_rx_mux_BLADERF_RX_MUX_12BIT_COUNTER,
+    /// A 32-bit counter incrementing once per sample, with the low 16 bits in I and the high 16
+    /// bits in Q. Check received buffers with [`verify_counter`].
     Counter32bit = bladerf_rx_mux_BLADERF_RX_MUX_32BIT_COUNTER,
     DigitalLoopback = bladerf_rx_mux_BLADERF_RX_MUX_DIGITAL_LOOPBACK,
 }
@@ -19,3 +23,50 @@ impl TryFrom<bladerf_rx_mux> for RxMux {
         Self::from_repr(value).ok_or_else(|| Error::msg(format!("Invalid RxMux value: {value}")))
     }
 }
+
+/// Check that samples received with [`RxMux::Counter32bit`] count up by one per sample,
+/// wrapping at `u32::MAX`.
+///
+/// A discontinuity means samples were dropped, e.g. by an overrun, and is reported as an error
+/// naming the index of the first sample that does not follow its predecessor.
+pub fn verify_counter(samples: &[Complex<i16>]) -> Result<()> {
+    let value =
+        |sample: &Complex<i16>| (sample.re as u16 as u32) | ((sample.im as u16 as u32) << 16);
+
+    for (i, pair) in samples.windows(2).enumerate() {
+        let (prev, next) = (value(&pair[0]), value(&pair[1]));
+        if next != prev.wrapping_add(1) {
+            return Err(Error::msg(format!(
+                "Counter discontinuity at sample {}: expected {}, got {next}",
+                i + 1,
+                prev.wrapping_add(1)
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counter(start: u32, len: usize) -> Vec<Complex<i16>> {
+        (0..len as u32)
+            .map(|i| {
+                let value = start.wrapping_add(i);
+                Complex::new(value as u16 as i16, (value >> 16) as u16 as i16)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn counter_verification() {
+        assert!(verify_counter(&counter(0xfffe, 8)).is_ok());
+        assert!(verify_counter(&counter(u32::MAX - 2, 8)).is_ok());
+
+        let mut samples = counter(100, 8);
+        samples.remove(5);
+        let err = verify_counter(&samples).unwrap_err();
+        assert!(err.to_string().contains("sample 5"), "{err}");
+    }
+}
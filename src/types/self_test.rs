@@ -0,0 +1,118 @@
+use num_complex::Complex;
+
+use crate::{brf_ci16_to_cf32, Loopback};
+
+/// Correlation a [`crate::BladeRF::self_test_loopback`] must reach to pass
+pub const SELF_TEST_MIN_CORRELATION: f32 = 0.9;
+
+/// Result of [`crate::BladeRF::self_test_loopback`]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SelfTestReport {
+    /// Loopback mode the waveform was sent through
+    pub loopback: Loopback,
+    /// Delay, in samples modulo the waveform period, at which the received samples matched best
+    pub lag: usize,
+    /// Normalized correlation between the sent and received waveform, from 0 to 1
+    pub correlation: f32,
+    /// Power of the matched waveform relative to the residual, in dB
+    pub snr_db: f32,
+    /// Whether `correlation` reached [`SELF_TEST_MIN_CORRELATION`]
+    pub passed: bool,
+}
+
+impl SelfTestReport {
+    /// Compare one period of a periodic `reference` waveform against as many `received` samples,
+    /// taken at an arbitrary point of the repeated transmission.
+    pub(crate) fn analyze(
+        loopback: Loopback,
+        reference: &[Complex<i16>],
+        received: &[Complex<i16>],
+    ) -> Self {
+        debug_assert_eq!(reference.len(), received.len());
+        let reference: Vec<_> = reference.iter().copied().map(brf_ci16_to_cf32).collect();
+        let received: Vec<_> = received.iter().copied().map(brf_ci16_to_cf32).collect();
+        let len = reference.len();
+
+        // Circular cross-correlation, the waveform repeats so any delay wraps around
+        let (lag, cross) = (0..len)
+            .map(|lag| {
+                let cross: Complex<f32> = reference
+                    .iter()
+                    .enumerate()
+                    .map(|(i, x)| received[(i + lag) % len] * x.conj())
+                    .sum();
+                (lag, cross)
+            })
+            .max_by(|(_, a), (_, b)| a.norm_sqr().total_cmp(&b.norm_sqr()))
+            .unwrap_or_default();
+
+        let energy = |s: &[Complex<f32>]| s.iter().map(|x| x.norm_sqr()).sum::<f32>();
+        let (ref_energy, rx_energy) = (energy(&reference), energy(&received));
+        let correlation = if ref_energy > 0.0 && rx_energy > 0.0 {
+            (cross.norm() / (ref_energy * rx_energy).sqrt()).min(1.0)
+        } else {
+            0.0
+        };
+
+        // Least squares fit of the reference: matched power is rho^2, the residual 1 - rho^2
+        let matched = correlation * correlation;
+        let snr_db = 10.0 * (matched / (1.0 - matched)).log10();
+
+        Self {
+            loopback,
+            lag,
+            correlation,
+            snr_db,
+            passed: correlation >= SELF_TEST_MIN_CORRELATION,
+        }
+    }
+}
+
+/// `len` QPSK symbols at half of full scale, modulated by a PRBS-15 sequence
+pub(crate) fn prbs_qpsk(len: usize) -> Vec<Complex<i16>> {
+    const LEVEL: i16 = 1024;
+
+    let mut state: u16 = 0x7fff;
+    let mut next_bit = || {
+        let bit = ((state >> 14) ^ (state >> 13)) & 1;
+        state = ((state << 1) | bit) & 0x7fff;
+        bit
+    };
+    let level = |bit: u16| if bit == 1 { LEVEL } else { -LEVEL };
+
+    (0..len)
+        .map(|_| Complex::new(level(next_bit()), level(next_bit())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loopback_analysis() {
+        let reference = prbs_qpsk(1024);
+
+        // Delayed, attenuated and rotated by 90 degrees, with a little noise
+        let received: Vec<_> = (0..reference.len())
+            .map(|i| {
+                let x = reference[(i + reference.len() - 100) % reference.len()];
+                let noise = if i % 7 == 0 { 20 } else { -3 };
+                Complex::new(-x.im / 2 + noise, x.re / 2)
+            })
+            .collect();
+        let report = SelfTestReport::analyze(Loopback::Firmware, &reference, &received);
+        assert_eq!(report.lag, 100);
+        assert!(report.passed, "{report:?}");
+        assert!(report.snr_db > 20.0, "{report:?}");
+
+        let unrelated: Vec<_> = prbs_qpsk(2048)[1024..].to_vec();
+        let report = SelfTestReport::analyze(Loopback::Firmware, &reference, &unrelated);
+        assert!(!report.passed, "{report:?}");
+
+        let silence = vec![Complex::new(0, 0); reference.len()];
+        let report = SelfTestReport::analyze(Loopback::Firmware, &reference, &silence);
+        assert_eq!(report.correlation, 0.0);
+        assert!(!report.passed);
+    }
+}
@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use log::warn;
+
+use crate::{DeviceSpeed, Error, Result};
+
+/// Buffering parameters for the synchronous interface, see [`crate::BladeRF::sync_config`], and
+/// for async streams such as [`crate::AsyncRxStream`].
+///
+/// libbladerf keeps `num_buffers` buffers of `samples_per_buffer` samples, of which
+/// `num_transfers` are in flight on the USB bus at any time. The remaining buffers hold samples
+/// waiting for (RX) or coming from (TX) the application. A ratio of roughly two buffers per
+/// transfer is a good starting point: fewer transfers under-utilize the bus, while using nearly
+/// every buffer for transfers leaves no slack for the application and risks stalls.
+///
+/// Buffer sizes are always a number of samples, not bytes, and must be a multiple of
+/// [`BUFFER_SIZE_MULTIPLE`]. One sample is one I/Q pair, so its size in bytes depends on the
+/// [`crate::Format`], and with a MIMO layout a buffer holds samples from both channels
+/// interleaved.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StreamConfig {
+    num_buffers: u32,
+    samples_per_buffer: u32,
+    num_transfers: u32,
+    timeout: Duration,
+}
+
+/// `samples_per_buffer` must be a multiple of this many samples
+pub const BUFFER_SIZE_MULTIPLE: u32 = 1024;
+
+impl StreamConfig {
+    /// Create a stream config, checking that `samples_per_buffer` is a non-zero multiple of
+    /// [`BUFFER_SIZE_MULTIPLE`] and that `num_transfers` is less than `num_buffers`.
+    ///
+    /// Logs a warning when fewer than half of the buffers are used for transfers.
+    pub fn new(
+        num_buffers: u32,
+        samples_per_buffer: u32,
+        num_transfers: u32,
+        timeout: Duration,
+    ) -> Result<Self> {
+        if samples_per_buffer == 0 || !samples_per_buffer.is_multiple_of(BUFFER_SIZE_MULTIPLE) {
+            return Err(Error::msg(format!(
+                "samples_per_buffer ({samples_per_buffer}) must be a non-zero multiple of \
+                 {BUFFER_SIZE_MULTIPLE} samples"
+            )));
+        }
+        if num_transfers == 0 {
+            return Err(Error::msg("num_transfers must be at least 1"));
+        }
+        if num_transfers >= num_buffers {
+            return Err(Error::msg(format!(
+                "num_transfers ({num_transfers}) must be less than num_buffers ({num_buffers})"
+            )));
+        }
+        if num_transfers < num_buffers / 2 {
+            warn!(
+                "Only {num_transfers} of {num_buffers} buffers are used for transfers, \
+                 consider about half of num_buffers"
+            );
+        }
+
+        Ok(Self {
+            num_buffers,
+            samples_per_buffer,
+            num_transfers,
+            timeout,
+        })
+    }
+
+    /// A reasonable config for the given USB speed.
+    ///
+    /// SuperSpeed links get larger and more numerous buffers so that high sample rates can be
+    /// sustained. Hi-Speed (USB 2.0) links are limited to roughly 40 MB/s, so smaller buffers are
+    /// used to keep latency in check. Unknown speeds use the Hi-Speed config.
+    pub fn default_for(speed: DeviceSpeed) -> Self {
+        let (num_buffers, samples_per_buffer, num_transfers) = match speed {
+            DeviceSpeed::SuperSpeed => (32, 32 * 1024, 16),
+            DeviceSpeed::HighSpeed | DeviceSpeed::Unknown => (16, 8 * 1024, 8),
+        };
+
+        Self {
+            num_buffers,
+            samples_per_buffer,
+            num_transfers,
+            timeout: Duration::from_secs(1),
+        }
+    }
+
+    pub fn num_buffers(&self) -> u32 {
+        self.num_buffers
+    }
+
+    /// Size of each buffer, in samples
+    pub fn samples_per_buffer(&self) -> u32 {
+        self.samples_per_buffer
+    }
+
+    pub fn num_transfers(&self) -> u32 {
+        self.num_transfers
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Roughly estimate whether this config can sustain `sample_rate` samples per second.
+    ///
+    /// This checks that a single buffer fills faster than the stream timeout, and that the
+    /// transfers in flight cover enough time to ride out scheduling hiccups on the host. It
+    /// cannot account for USB bus contention or host load.
+    pub fn validate_throughput(&self, sample_rate: u32) -> Result<()> {
+        /// Minimum amount of samples, in time, that should be queued with the USB stack
+        const MIN_IN_FLIGHT: Duration = Duration::from_millis(1);
+
+        if sample_rate == 0 {
+            return Err(Error::msg("sample rate must be non-zero"));
+        }
+        let rate = sample_rate as f64;
+
+        let buffer_time = Duration::from_secs_f64(self.samples_per_buffer as f64 / rate);
+        if buffer_time >= self.timeout {
+            return Err(Error::msg(format!(
+                "A buffer of {} samples takes {buffer_time:?} at {sample_rate} Hz, \
+                 which exceeds the stream timeout of {:?}",
+                self.samples_per_buffer, self.timeout
+            )));
+        }
+
+        let in_flight = Duration::from_secs_f64(
+            self.num_transfers as f64 * self.samples_per_buffer as f64 / rate,
+        );
+        if in_flight < MIN_IN_FLIGHT {
+            return Err(Error::msg(format!(
+                "{} transfers of {} samples only cover {in_flight:?} at {sample_rate} Hz, \
+                 increase num_transfers or samples_per_buffer",
+                self.num_transfers, self.samples_per_buffer
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_config_validation() {
+        let timeout = Duration::from_millis(100);
+
+        assert!(StreamConfig::new(16, 8192, 8, timeout).is_ok());
+        assert!(StreamConfig::new(16, 8192, 16, timeout).is_err());
+        assert!(StreamConfig::new(16, 8192, 0, timeout).is_err());
+        assert!(StreamConfig::new(16, 0, 8, timeout).is_err());
+        assert!(StreamConfig::new(16, 1000, 8, timeout).is_err());
+
+        let config = StreamConfig::new(16, 8192, 8, timeout).unwrap();
+        assert!(config.validate_throughput(1_000_000).is_ok());
+        // A single buffer takes longer than the timeout to fill
+        assert!(config.validate_throughput(10_000).is_err());
+
+        let config = StreamConfig::new(2, 1024, 1, timeout).unwrap();
+        // Under a millisecond of samples in flight
+        assert!(config.validate_throughput(61_440_000).is_err());
+
+        for speed in [DeviceSpeed::HighSpeed, DeviceSpeed::SuperSpeed] {
+            let config = StreamConfig::default_for(speed);
+            assert_eq!(
+                StreamConfig::new(
+                    config.num_buffers(),
+                    config.samples_per_buffer(),
+                    config.num_transfers(),
+                    config.timeout()
+                ),
+                Ok(config)
+            );
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Instant,
+};
+
+/// Weight of the newest transfer in [`StreamStats::throughput`]
+const THROUGHPUT_SMOOTHING: f64 = 0.1;
+
+/// Cumulative counters of one direction of the synchronous interface, see
+/// [`crate::BladeRF::rx_stats`] and [`crate::BladeRF::tx_stats`].
+///
+/// The counters are atomics, so they can be read from another thread while streaming, e.g. to
+/// update a status display.
+#[derive(Debug)]
+pub struct StreamStats {
+    samples: AtomicU64,
+    bytes: AtomicU64,
+    /// Time of the last transfer, in nanoseconds since `epoch`. 0 before the first transfer.
+    last_transfer_ns: AtomicU64,
+    /// Bits of the `f64` moving average, in bytes per second
+    throughput: AtomicU64,
+    epoch: Instant,
+}
+
+impl StreamStats {
+    pub(crate) fn new() -> Self {
+        Self {
+            samples: AtomicU64::new(0),
+            bytes: AtomicU64::new(0),
+            last_transfer_ns: AtomicU64::new(0),
+            throughput: AtomicU64::new(0f64.to_bits()),
+            epoch: Instant::now(),
+        }
+    }
+
+    /// Count a completed transfer
+    pub(crate) fn record(&self, samples: usize, bytes: usize) {
+        self.samples.fetch_add(samples as u64, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+
+        let now = (self.epoch.elapsed().as_nanos() as u64).max(1);
+        let last = self.last_transfer_ns.swap(now, Ordering::Relaxed);
+        // The first transfer has no interval to measure a rate over
+        if last == 0 || now <= last {
+            return;
+        }
+
+        let rate = bytes as f64 / ((now - last) as f64 / 1e9);
+        let old = f64::from_bits(self.throughput.load(Ordering::Relaxed));
+        let new = if old == 0.0 {
+            rate
+        } else {
+            old + THROUGHPUT_SMOOTHING * (rate - old)
+        };
+        self.throughput.store(new.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Samples transferred since the device was opened
+    pub fn total_samples(&self) -> u64 {
+        self.samples.load(Ordering::Relaxed)
+    }
+
+    /// Bytes transferred since the device was opened
+    pub fn total_bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// Moving average of the throughput over roughly the last ten transfers, in bytes per second.
+    ///
+    /// Each transfer is rated by its size over the time since the previous one, so the average
+    /// only moves while transfers complete and keeps its last value once streaming stops.
+    pub fn throughput(&self) -> f64 {
+        f64::from_bits(self.throughput.load(Ordering::Relaxed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{thread, time::Duration};
+
+    #[test]
+    fn stream_stats_counting() {
+        let stats = StreamStats::new();
+        stats.record(1024, 4096);
+        assert_eq!(stats.throughput(), 0.0);
+
+        thread::sleep(Duration::from_millis(10));
+        stats.record(1024, 4096);
+        assert_eq!(stats.total_samples(), 2048);
+        assert_eq!(stats.total_bytes(), 8192);
+
+        // At most 4096 bytes in the 10 ms since the first transfer
+        let throughput = stats.throughput();
+        assert!(throughput > 0.0 && throughput <= 409_600.0, "{throughput}");
+    }
+}
@@ -0,0 +1,41 @@
+use strum::FromRepr;
+
+use crate::{sys::*, Error, Result};
+
+/// Reference used to discipline the VCTCXO
+#[derive(Copy, Clone, Debug, FromRepr, PartialEq, Eq)]
+#[repr(i32)]
+pub enum VctcxoTamerMode {
+    /// Free running, trimmed only by the trim DAC
+    Disabled = bladerf_vctcxo_tamer_mode_BLADERF_VCTCXO_TAMER_DISABLED,
+    /// Discipline from a 1 PPS signal on the J71 header
+    Pps1 = bladerf_vctcxo_tamer_mode_BLADERF_VCTCXO_TAMER_1_PPS,
+    /// Discipline from a 10 MHz reference on the J71 header
+    Pps10MHz = bladerf_vctcxo_tamer_mode_BLADERF_VCTCXO_TAMER_10_MHZ,
+}
+
+impl TryFrom<bladerf_vctcxo_tamer_mode> for VctcxoTamerMode {
+    type Error = Error;
+
+    fn try_from(value: bladerf_vctcxo_tamer_mode) -> Result<Self> {
+        Self::from_repr(value)
+            .ok_or_else(|| Error::msg(format!("Invalid VctcxoTamerMode value: {value}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vctcxo_tamer_mode_invalid() {
+        assert_eq!(
+            VctcxoTamerMode::try_from(bladerf_vctcxo_tamer_mode_BLADERF_VCTCXO_TAMER_1_PPS),
+            Ok(VctcxoTamerMode::Pps1)
+        );
+        assert!(
+            VctcxoTamerMode::try_from(bladerf_vctcxo_tamer_mode_BLADERF_VCTCXO_TAMER_INVALID)
+                .is_err()
+        );
+    }
+}
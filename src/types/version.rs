@@ -1,7 +1,9 @@
-use std::{cmp, ffi::CStr};
+use std::{cmp, ffi::CStr, str::FromStr};
 
 use libbladerf_sys::bladerf_version;
 
+use crate::Error;
+
 #[derive(Copy, Clone, Debug)]
 pub struct Version {
     pub major: u16,
@@ -12,6 +14,16 @@ pub struct Version {
 }
 
 impl Version {
+    /// A version without a description, e.g. to compare against a minimum required version
+    pub const fn new(major: u16, minor: u16, patch: u16) -> Self {
+        Version {
+            major,
+            minor,
+            patch,
+            describe: None,
+        }
+    }
+
     /// Converts the ffi type `bladerf_version` to `Self`.
     ///
     /// # Safety
@@ -54,6 +66,35 @@ impl std::fmt::Display for Version {
     }
 }
 
+/// Parses `"2.5.0"` or `"v2.5.0"`, as printed by [`Version`]'s `Display`. A trailing description
+/// in parentheses is accepted but discarded, so `describe` is always `None`.
+impl FromStr for Version {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::msg(format!("Invalid version string: {s:?}"));
+
+        let trimmed = s.trim();
+        let numbers = match trimmed.split_once('(') {
+            Some((numbers, describe)) if describe.ends_with(')') => numbers.trim_end(),
+            Some(_) => return Err(invalid()),
+            None => trimmed,
+        };
+        let numbers = numbers.strip_prefix('v').unwrap_or(numbers);
+
+        let mut parts = numbers
+            .split('.')
+            .map(|p| p.parse::<u16>().map_err(|_| invalid()));
+        let (Some(major), Some(minor), Some(patch), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+
+        Ok(Version::new(major?, minor?, patch?))
+    }
+}
+
 impl PartialOrd for Version {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         Some(self.cmp(other))
@@ -174,4 +215,30 @@ mod tests {
         assert!(v2 < v3);
         assert!(v1 < v3);
     }
+
+    #[test]
+    fn version_parse() {
+        let expected = Version::new(2, 5, 0);
+
+        for s in ["2.5.0", "v2.5.0", " v2.5.0 ", "v2.5.0 (2.5.0-git-abcdef)"] {
+            let v: Version = s.parse().unwrap();
+            assert_eq!(v, expected);
+            assert_eq!(v.describe, None);
+        }
+
+        assert_eq!(expected.to_string().parse::<Version>(), Ok(expected));
+        assert!(Version::new(2, 4, 0) < "v2.5.0".parse().unwrap());
+
+        for s in [
+            "",
+            "v",
+            "2.5",
+            "2.5.0.1",
+            "2.x.0",
+            "-1.0.0",
+            "v2.5.0 (unterminated",
+        ] {
+            assert!(s.parse::<Version>().is_err(), "{s:?} should not parse");
+        }
+    }
 }